@@ -13,9 +13,17 @@ EPSILON = 10 ** (-6)
 @dataclass
 class SolverStats:
     objective: int
+    feasible: bool
+    initial: str
+    pricing: str
+    initial_objective: int
     iterations: int
     avg_chain_len: float
+    m: int
     n: int
+    unmet_demand: list[int]
+    leftover_supply: list[int]
+    warm_start: dict | None
 
 
 try:
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+
+use crate::cost::Cost;
+use crate::error::SolverError;
+use crate::number::Number;
+
+/// Assignment of rows (workers) to columns (jobs), each one used at most once.
+/// If there are more rows than columns, some rows stay unassigned
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Assignment<T = i32> {
+    pub costs: Vec<Vec<T>>,
+    /// Pairs (row, column), which can't be assigned
+    #[serde(default)]
+    pub forbidden: Vec<(usize, usize)>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AssignmentSolution<T = i32> {
+    /// Column of every row, `None` if the row is unassigned
+    pub assignment: Vec<Option<usize>>,
+    pub objective: T,
+    /// Dual labels of rows and columns. `u[i] + v[j]` doesn't exceed the cost
+    /// of any allowed pair, equals it for assigned ones, and the labels add up
+    /// to the objective. Labels of the longer side are non-positive, and zero
+    /// for unassigned rows or columns
+    pub u: Vec<T>,
+    pub v: Vec<T>,
+}
+
+impl<T: Number> Assignment<T> {
+    /// Checks that the data is consistent, so that the problem can be solved
+    pub fn validate(&self) -> Result<(), SolverError<T>> {
+        let n = self.costs.first().map_or(0, |row| row.len());
+        if n == 0 {
+            return Err(SolverError::Empty);
+        }
+        if let Some(row) = self.costs.iter().find(|row| row.len() != n) {
+            return Err(SolverError::DimensionMismatch {
+                what: "cost columns".to_string(),
+                expected: n,
+                found: row.len(),
+            });
+        }
+        if let Some((i, j)) = self
+            .forbidden
+            .iter()
+            .find(|(i, j)| *i >= self.costs.len() || *j >= n)
+        {
+            return Err(SolverError::RouteOutOfBounds {
+                source: *i,
+                destination: *j,
+            });
+        }
+        Ok(())
+    }
+
+    /// Hungarian (Kuhn-Munkres) algorithm in O(n²m), where n <= m. Rows are added
+    /// one by one along the shortest augmenting path, while the labels are kept
+    /// feasible, so forbidden pairs are used only if there is no other way
+    pub fn solve(&self) -> Result<AssignmentSolution<T>, SolverError<T>> {
+        self.validate()?;
+        let mut weights = self
+            .costs
+            .iter()
+            .map(|row| row.iter().map(|x| Cost::new(*x)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for (i, j) in self.forbidden.iter() {
+            weights[*i][*j] = Cost::forbidden();
+        }
+
+        // Algorithm needs at least as many columns as rows
+        let transposed = weights.len() > weights[0].len();
+        if transposed {
+            weights = (0..weights[0].len())
+                .map(|j| weights.iter().map(|row| row[j]).collect())
+                .collect();
+        }
+        let (n, m) = (weights.len(), weights[0].len());
+
+        // Index 0 is a fictive column, which holds the row being added
+        let (mut u, mut v) = (vec![Cost::default(); n + 1], vec![Cost::default(); m + 1]);
+        let mut rows = vec![0; m + 1];
+        let mut way = vec![0; m + 1];
+        for i in 1..=n {
+            rows[0] = i;
+            let mut j0 = 0;
+            let mut min_reduced = vec![None; m + 1];
+            let mut used = vec![false; m + 1];
+            loop {
+                used[j0] = true;
+                let i0 = rows[j0];
+                let mut delta = None;
+                let mut j1 = 0;
+                for j in (1..=m).filter(|j| !used[*j]) {
+                    let reduced = weights[i0 - 1][j - 1] - u[i0] - v[j];
+                    if min_reduced[j].is_none_or(|x| reduced < x) {
+                        min_reduced[j] = Some(reduced);
+                        way[j] = j0;
+                    }
+                    let candidate = min_reduced[j].expect("Reduced cost is set");
+                    if delta.is_none_or(|x| candidate < x) {
+                        delta = Some(candidate);
+                        j1 = j;
+                    }
+                }
+
+                let delta = delta.expect("There are enough columns");
+                for j in 0..=m {
+                    match used[j] {
+                        true => {
+                            u[rows[j]] = u[rows[j]] + delta;
+                            v[j] = v[j] - delta;
+                        }
+                        false => min_reduced[j] = min_reduced[j].map(|x| x - delta),
+                    }
+                }
+                j0 = j1;
+                if rows[j0] == 0 {
+                    break;
+                }
+            }
+
+            // Augment along the path
+            while j0 != 0 {
+                let j1 = way[j0];
+                rows[j0] = rows[j1];
+                j0 = j1;
+            }
+        }
+
+        let mut assignment = vec![None; n];
+        for (j, i) in rows.iter().enumerate().skip(1).filter(|(_, i)| **i != 0) {
+            assignment[i - 1] = Some(j - 1);
+        }
+        let objective = assignment
+            .iter()
+            .enumerate()
+            .try_fold(Cost::default(), |acc, (i, j)| {
+                acc.checked_add(weights[i][j.expect("Every row is assigned")])
+            })
+            .ok_or(SolverError::Overflow)?;
+        if objective.is_forbidden() {
+            return Err(SolverError::Infeasible);
+        }
+
+        let (mut u, mut v) = (
+            u[1..].iter().map(|x| x.val).collect::<Vec<_>>(),
+            v[1..].iter().map(|x| x.val).collect::<Vec<_>>(),
+        );
+        if transposed {
+            let mut columns = vec![None; m];
+            for (j, i) in assignment.iter().enumerate() {
+                columns[i.expect("Every row is assigned")] = Some(j);
+            }
+            assignment = columns;
+            (u, v) = (v, u);
+        }
+
+        Ok(AssignmentSolution {
+            assignment,
+            objective: objective.val,
+            u,
+            v,
+        })
+    }
+}
@@ -0,0 +1,251 @@
+//! Solves a transportation problem from a file and prints the plan.
+//!
+//! Exits with 1 for unreadable or invalid input, 2 for wrong arguments,
+//! 3 for infeasible problems and 4 if the iteration limit was reached
+
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use clap::{Parser, ValueEnum};
+use num_rational::Rational64;
+
+use transportation_problem::error::SolverError;
+use transportation_problem::io::{read_edges, read_matrix};
+use transportation_problem::number::Number;
+use transportation_problem::problem::{Balancing, Named, Problem};
+use transportation_problem::solver::initial::*;
+use transportation_problem::solver::pricing::*;
+use transportation_problem::solver::TransportationSolver;
+
+#[derive(Parser)]
+#[command(name = "transport", about = "Solves transportation problems")]
+struct Args {
+    /// Problem as JSON, or as a CSV cost matrix. With `--supply` and `--demand`,
+    /// a CSV edge list `from,to,cost[,capacity]`
+    problem: PathBuf,
+    /// CSV `name,supply` of sources of an edge list
+    #[arg(long, requires = "demand", conflicts_with = "input")]
+    supply: Option<PathBuf>,
+    /// CSV `name,demand` of destinations of an edge list
+    #[arg(long, requires = "supply")]
+    demand: Option<PathBuf>,
+    /// Input format, guessed from the extension by default.
+    /// Edge lists are always CSV
+    #[arg(long, value_enum)]
+    input: Option<Input>,
+    #[arg(long, value_enum, default_value_t = Numbers::Integer)]
+    numbers: Numbers,
+    #[arg(long, value_enum, default_value_t = Initial::NorthWest)]
+    initial: Initial,
+    #[arg(long, value_enum, default_value_t = Rule::Dantzig)]
+    pricing: Rule,
+    /// Reject problems, where total supply differs from total demand
+    #[arg(long)]
+    strict: bool,
+    /// Give up after this many iterations
+    #[arg(long)]
+    max_iterations: Option<usize>,
+    #[arg(long, value_enum, default_value_t = Output::Table)]
+    output: Output,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Input {
+    Json,
+    Csv,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Numbers {
+    Integer,
+    Float,
+    /// Fractions like `3/4`
+    Rational,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Initial {
+    NorthWest,
+    LeastCost,
+    Vogel,
+    Russell,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Rule {
+    Dantzig,
+    FirstImproving,
+    Partial,
+    Bland,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Output {
+    Table,
+    Json,
+}
+
+/// Failure of the command along with its exit code
+struct Failure {
+    code: u8,
+    message: String,
+}
+
+impl<T: Display> From<SolverError<T>> for Failure {
+    fn from(error: SolverError<T>) -> Self {
+        let code = match error {
+            SolverError::Infeasible => 3,
+            SolverError::IterationLimit { .. } => 4,
+            _ => 1,
+        };
+        Failure {
+            code,
+            message: error.to_string(),
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<String, Failure> {
+    fs::read_to_string(path).map_err(|error| Failure {
+        code: 1,
+        message: format!("Can't read {}: {error}", path.display()),
+    })
+}
+
+fn load<T: Number + FromStr>(args: &Args) -> Result<Problem<T>, Failure> {
+    let text = read(&args.problem)?;
+    if let (Some(supply), Some(demand)) = (&args.supply, &args.demand) {
+        return Ok(read_edges(&text, &read(supply)?, &read(demand)?)?);
+    }
+    let json = match args.input {
+        Some(input) => matches!(input, Input::Json),
+        None => args.problem.extension().is_some_and(|x| x == "json"),
+    };
+    match json {
+        true => serde_json::from_str(&text).map_err(|error| Failure {
+            code: 1,
+            message: format!("Invalid problem: {error}"),
+        }),
+        false => Ok(read_matrix(&text)?),
+    }
+}
+
+fn solve_priced<T: Number, I: InitialSolution>(
+    solver: &mut TransportationSolver<T>,
+    rule: Rule,
+) -> Result<(), SolverError<T>> {
+    match rule {
+        Rule::Dantzig => solver.solve_using::<I, Dantzig>(),
+        Rule::FirstImproving => solver.solve_using::<I, FirstImproving>(),
+        Rule::Partial => solver.solve_using::<I, Partial>(),
+        Rule::Bland => solver.solve_using::<I, Bland>(),
+    }
+}
+
+fn solve<T: Number>(
+    solver: &mut TransportationSolver<T>,
+    args: &Args,
+) -> Result<(), SolverError<T>> {
+    match args.initial {
+        Initial::NorthWest => solve_priced::<T, NorthWest>(solver, args.pricing),
+        Initial::LeastCost => solve_priced::<T, LeastCost>(solver, args.pricing),
+        Initial::Vogel => solve_priced::<T, Vogel>(solver, args.pricing),
+        Initial::Russell => solve_priced::<T, Russell>(solver, args.pricing),
+    }
+}
+
+/// Columns aligned with spaces
+fn table(rows: &[Vec<String>]) -> String {
+    let widths = (0..rows[0].len())
+        .map(|col| rows.iter().map(|row| row[col].len()).max().unwrap_or(0))
+        .collect::<Vec<_>>();
+    let mut text = String::new();
+    for row in rows {
+        let line = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        text += line.trim_end();
+        text += "\n";
+    }
+    text
+}
+
+fn run<T: Number + FromStr>(args: &Args) -> Result<String, Failure> {
+    let problem = load::<T>(args)?;
+    let balancing = match args.strict {
+        true => Balancing::Strict,
+        false => Balancing::default(),
+    };
+    let mut solver = TransportationSolver::with_balancing(problem.clone(), balancing)?;
+    solver.max_iterations = args.max_iterations;
+    solve(&mut solver, args)?;
+    let solution = solver.solution().expect("Problem is solved");
+
+    if let Output::Json = args.output {
+        return Ok(
+            serde_json::to_string_pretty(&solution).expect("Solution is serializable") + "\n",
+        );
+    }
+    let mut rows = vec![["From", "To", "Amount", "Cost"].map(String::from).to_vec()];
+    rows.extend(solution.routes().map(|route| {
+        vec![
+            solution.source_name(route.source),
+            solution.destination_name(route.destination),
+            route.amount.to_string(),
+            (route.amount * problem.costs[route.source][route.destination]).to_string(),
+        ]
+    }));
+    let stats = &solution.stats;
+    let mut text = table(&rows);
+    text += &format!("\nObjective: {}\n", solution.objective);
+    text += &format!(
+        "Initial solution: {} with objective {}\n",
+        stats.initial, stats.initial_objective
+    );
+    text += &format!(
+        "Iterations: {} with {} pricing, average chain length {:.2}\n",
+        stats.iterations, stats.pricing, stats.avg_chain_len
+    );
+    let shortfall = |amounts: &[T], name: &dyn Fn(usize) -> String| {
+        amounts
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| x.is_positive())
+            .map(|(idx, x)| format!("{} {x}", name(idx)))
+            .collect::<Vec<_>>()
+    };
+    let unmet = shortfall(&stats.unmet_demand, &|j| solution.destination_name(j));
+    if !unmet.is_empty() {
+        text += &format!("Unmet demand: {}\n", unmet.join(", "));
+    }
+    let leftover = shortfall(&stats.leftover_supply, &|i| solution.source_name(i));
+    if !leftover.is_empty() {
+        text += &format!("Leftover supply: {}\n", leftover.join(", "));
+    }
+    Ok(text)
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let result = match args.numbers {
+        Numbers::Integer => run::<i64>(&args),
+        Numbers::Float => run::<f64>(&args),
+        Numbers::Rational => run::<Rational64>(&args),
+    };
+    match result {
+        Ok(text) => {
+            print!("{text}");
+            ExitCode::SUCCESS
+        }
+        Err(failure) => {
+            eprintln!("error: {}", failure.message);
+            ExitCode::from(failure.code)
+        }
+    }
+}
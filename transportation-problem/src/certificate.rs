@@ -0,0 +1,379 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::number::Number;
+use crate::problem::Problem;
+use crate::solution::Route;
+use crate::sparse::SparseProblem;
+
+/// Condition of optimality, which doesn't hold for a solution
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Violation<T = i32> {
+    /// Length of some data doesn't match the number of sources or destinations,
+    /// so nothing else is checked
+    DimensionMismatch {
+        what: String,
+        expected: usize,
+        found: usize,
+    },
+    /// Forbidden or capacitated route refers to a nonexistent source or destination
+    RouteOutOfBounds { source: usize, destination: usize },
+    /// Shipments from the source don't add up to its supply
+    Supply {
+        source: usize,
+        expected: T,
+        found: T,
+    },
+    /// Shipments to the destination don't add up to its demand
+    Demand {
+        destination: usize,
+        expected: T,
+        found: T,
+    },
+    NegativeFlow {
+        source: usize,
+        destination: usize,
+        flow: T,
+    },
+    ForbiddenFlow {
+        source: usize,
+        destination: usize,
+        flow: T,
+    },
+    /// Route carries more goods than its capacity
+    CapacityExceeded {
+        source: usize,
+        destination: usize,
+        flow: T,
+        capacity: T,
+    },
+    /// Route is cheaper than the potentials allow, but not used up to its
+    /// capacity, so the dual is infeasible
+    NegativeReducedCost {
+        source: usize,
+        destination: usize,
+        reduced_cost: T,
+    },
+    /// Goods are shipped along a route with a non-zero reduced cost
+    ComplementarySlackness {
+        source: usize,
+        destination: usize,
+        flow: T,
+        reduced_cost: T,
+    },
+}
+
+impl<T: Display> Display for Violation<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::DimensionMismatch {
+                what,
+                expected,
+                found,
+            } => write!(f, "Expected {expected} {what}, found {found}"),
+            Violation::RouteOutOfBounds {
+                source,
+                destination,
+            } => write!(f, "Route from {source} to {destination} is out of bounds"),
+            Violation::Supply {
+                source,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Source {source} ships {found} instead of its supply {expected}"
+            ),
+            Violation::Demand {
+                destination,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Destination {destination} receives {found} instead of its demand {expected}"
+            ),
+            Violation::NegativeFlow {
+                source,
+                destination,
+                flow,
+            } => write!(
+                f,
+                "Route from {source} to {destination} has a negative flow {flow}"
+            ),
+            Violation::ForbiddenFlow {
+                source,
+                destination,
+                flow,
+            } => write!(
+                f,
+                "Forbidden route from {source} to {destination} is used for {flow}"
+            ),
+            Violation::CapacityExceeded {
+                source,
+                destination,
+                flow,
+                capacity,
+            } => write!(
+                f,
+                "Route from {source} to {destination} is used for {flow}, \
+                 but its capacity is {capacity}"
+            ),
+            Violation::NegativeReducedCost {
+                source,
+                destination,
+                reduced_cost,
+            } => write!(
+                f,
+                "Route from {source} to {destination} has a negative reduced cost {reduced_cost}"
+            ),
+            Violation::ComplementarySlackness {
+                source,
+                destination,
+                flow,
+                reduced_cost,
+            } => write!(
+                f,
+                "Route from {source} to {destination} is used for {flow}, \
+                 but its reduced cost is {reduced_cost}"
+            ),
+        }
+    }
+}
+
+/// Proof of optimality of a transportation plan: the plan is primal feasible,
+/// potentials are dual feasible and both satisfy complementary slackness
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Certificate<T = i32> {
+    pub objective: T,
+    /// Objective of the dual problem, equal to the primal one at the optimum
+    pub dual_objective: T,
+    pub violations: Vec<Violation<T>>,
+}
+
+impl<T: Number> Certificate<T> {
+    /// Checks the flows of a balanced problem against the potentials
+    /// of sources `u` and destinations `v`. Routes at their capacities may have
+    /// negative reduced costs, which are the duals of capacities
+    pub fn check(problem: &Problem<T>, flows: &[Vec<T>], u: &[T], v: &[T]) -> Self {
+        let violations = Self::mismatches(problem, flows, u, v);
+        if !violations.is_empty() {
+            return Self::invalid(violations);
+        }
+        let flows = flows
+            .iter()
+            .enumerate()
+            .flat_map(|(source, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(move |(destination, amount)| Route {
+                        source,
+                        destination,
+                        amount: *amount,
+                    })
+            })
+            .collect::<Vec<_>>();
+        Self::check_routes(&problem.sparse(), &flows, u, v)
+    }
+
+    /// Same as `check` for a problem storing only its lanes, with the flows
+    /// along routes. Routes, which aren't listed, carry nothing, and those,
+    /// which aren't lanes, are forbidden, so that only listed ones are visited
+    pub fn check_routes(problem: &SparseProblem<T>, flows: &[Route<T>], u: &[T], v: &[T]) -> Self {
+        let mut violations = Self::lane_mismatches(problem, flows, u, v);
+        if !violations.is_empty() {
+            return Self::invalid(violations);
+        }
+        let (m, n) = (problem.supply.len(), problem.demand.len());
+        let mut routes = problem
+            .lanes
+            .iter()
+            .map(|x| ((x.source, x.destination), (Some(x), T::zero())))
+            .collect::<BTreeMap<_, _>>();
+        let (mut shipped, mut received) = (vec![T::zero(); m], vec![T::zero(); n]);
+        for route in flows.iter() {
+            let key = (route.source, route.destination);
+            routes.entry(key).or_insert((None, T::zero())).1 += route.amount;
+            shipped[route.source] += route.amount;
+            received[route.destination] += route.amount;
+        }
+        let mut capacity_duals = T::zero();
+
+        // Primal feasibility
+        for (source, (found, expected)) in
+            shipped.into_iter().zip(problem.supply.iter()).enumerate()
+        {
+            if found.compare(expected).is_ne() {
+                violations.push(Violation::Supply {
+                    source,
+                    expected: *expected,
+                    found,
+                });
+            }
+        }
+        for (destination, (found, expected)) in
+            received.into_iter().zip(problem.demand.iter()).enumerate()
+        {
+            if found.compare(expected).is_ne() {
+                violations.push(Violation::Demand {
+                    destination,
+                    expected: *expected,
+                    found,
+                });
+            }
+        }
+
+        let mut objective = T::zero();
+        for ((source, destination), (lane, flow)) in routes.into_iter() {
+            if flow.is_negative() {
+                violations.push(Violation::NegativeFlow {
+                    source,
+                    destination,
+                    flow,
+                });
+            }
+
+            // Forbidden routes don't exist in the dual
+            let Some(lane) = lane else {
+                if !flow.is_zero() {
+                    violations.push(Violation::ForbiddenFlow {
+                        source,
+                        destination,
+                        flow,
+                    });
+                }
+                continue;
+            };
+            if let Some(capacity) = lane.capacity.filter(|x| flow.compare(x).is_gt()) {
+                violations.push(Violation::CapacityExceeded {
+                    source,
+                    destination,
+                    flow,
+                    capacity,
+                });
+            }
+            objective += lane.cost * flow;
+
+            // Dual feasibility and complementary slackness
+            let reduced_cost = lane.cost - u[source] - v[destination];
+            let saturated = lane.capacity.is_some_and(|x| flow.compare(&x).is_eq());
+            if reduced_cost.is_negative() && saturated {
+                capacity_duals += reduced_cost * flow;
+            } else if reduced_cost.is_negative() {
+                violations.push(Violation::NegativeReducedCost {
+                    source,
+                    destination,
+                    reduced_cost,
+                });
+            } else if !reduced_cost.is_zero() && flow.is_positive() {
+                violations.push(Violation::ComplementarySlackness {
+                    source,
+                    destination,
+                    flow,
+                    reduced_cost,
+                });
+            }
+        }
+
+        let dual_objective = problem
+            .supply
+            .iter()
+            .zip(u.iter())
+            .chain(problem.demand.iter().zip(v.iter()))
+            .map(|(quantity, potential)| *quantity * *potential)
+            .sum::<T>()
+            + capacity_duals;
+
+        Certificate {
+            objective,
+            dual_objective,
+            violations,
+        }
+    }
+
+    /// Certificate of data, which can't be checked
+    fn invalid(violations: Vec<Violation<T>>) -> Self {
+        Certificate {
+            objective: T::zero(),
+            dual_objective: T::zero(),
+            violations,
+        }
+    }
+
+    /// Data, which doesn't fit the numbers of sources and destinations
+    fn mismatches(problem: &Problem<T>, flows: &[Vec<T>], u: &[T], v: &[T]) -> Vec<Violation<T>> {
+        let (m, n) = (problem.supply.len(), problem.demand.len());
+        let mismatch = |what: &str, expected: usize, found: usize| {
+            (expected != found).then(|| Violation::DimensionMismatch {
+                what: what.to_string(),
+                expected,
+                found,
+            })
+        };
+        let columns =
+            |what: &str, rows: &[Vec<T>]| rows.iter().find_map(|row| mismatch(what, n, row.len()));
+        let routes = problem
+            .forbidden
+            .iter()
+            .copied()
+            .chain(problem.capacities.iter().map(|(i, j, _)| (*i, *j)))
+            .filter(|(i, j)| *i >= m || *j >= n)
+            .map(|(source, destination)| Violation::RouteOutOfBounds {
+                source,
+                destination,
+            });
+        [
+            mismatch("cost rows", m, problem.costs.len()),
+            columns("cost columns", &problem.costs),
+            mismatch("flow rows", m, flows.len()),
+            columns("flow columns", flows),
+            mismatch("source potentials", m, u.len()),
+            mismatch("destination potentials", n, v.len()),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(routes)
+        .collect()
+    }
+
+    /// Potentials, which don't fit the numbers of sources and destinations, along
+    /// with lanes and flows referring to nonexistent ones
+    fn lane_mismatches(
+        problem: &SparseProblem<T>,
+        flows: &[Route<T>],
+        u: &[T],
+        v: &[T],
+    ) -> Vec<Violation<T>> {
+        let (m, n) = (problem.supply.len(), problem.demand.len());
+        let mismatch = |what: &str, expected: usize, found: usize| {
+            (expected != found).then(|| Violation::DimensionMismatch {
+                what: what.to_string(),
+                expected,
+                found,
+            })
+        };
+        let routes = problem
+            .lanes
+            .iter()
+            .map(|x| (x.source, x.destination))
+            .chain(flows.iter().map(|x| (x.source, x.destination)))
+            .filter(|(i, j)| *i >= m || *j >= n)
+            .map(|(source, destination)| Violation::RouteOutOfBounds {
+                source,
+                destination,
+            });
+        [
+            mismatch("source potentials", m, u.len()),
+            mismatch("destination potentials", n, v.len()),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(routes)
+        .collect()
+    }
+
+    /// Plan is optimal if no condition is violated
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
@@ -0,0 +1,222 @@
+use good_lp::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SolverError;
+use crate::number::Number;
+use crate::problem::Problem;
+
+/// Several commodities shipped between the same sources and destinations,
+/// which share capacities of the routes
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MultiCommodity {
+    /// Costs, supplies, demands, forbidden routes and capacities of every commodity
+    pub commodities: Vec<Problem<f64>>,
+    /// Routes (source, destination, capacity) shared by all commodities.
+    /// If a route is listed several times, the smallest one applies
+    #[serde(default)]
+    pub capacities: Vec<(usize, usize, f64)>,
+    /// Whether flows must be whole units, which turns the model into a MILP
+    #[serde(default)]
+    pub integral: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MultiCommoditySolution {
+    /// Goods of every commodity shipped from every source to every destination
+    pub flows: Vec<Vec<Vec<f64>>>,
+    pub objective: f64,
+    /// Shipping cost of every commodity
+    pub costs: Vec<f64>,
+    /// Demand of every destination, which can't be met, per commodity
+    pub unmet_demand: Vec<Vec<f64>>,
+    /// Supply of every source, which isn't needed, per commodity
+    pub leftover_supply: Vec<Vec<f64>>,
+}
+
+impl MultiCommodity {
+    /// Checks that the data is consistent, so that the model can be solved
+    pub fn validate(&self) -> Result<(), SolverError<f64>> {
+        let first = self.commodities.first().ok_or(SolverError::Empty)?;
+        let (m, n) = (first.supply.len(), first.demand.len());
+        for commodity in self.commodities.iter() {
+            commodity.validate()?;
+            if commodity.supply.len() != m {
+                return Err(SolverError::DimensionMismatch {
+                    what: "sources".to_string(),
+                    expected: m,
+                    found: commodity.supply.len(),
+                });
+            }
+            if commodity.demand.len() != n {
+                return Err(SolverError::DimensionMismatch {
+                    what: "destinations".to_string(),
+                    expected: n,
+                    found: commodity.demand.len(),
+                });
+            }
+        }
+        if let Some((i, j, _)) = self.capacities.iter().find(|(i, j, _)| *i >= m || *j >= n) {
+            return Err(SolverError::RouteOutOfBounds {
+                source: *i,
+                destination: *j,
+            });
+        }
+        if let Some((i, j, x)) = self.capacities.iter().find(|(_, _, x)| x.is_negative()) {
+            return Err(SolverError::NegativeCapacity {
+                source: *i,
+                destination: *j,
+                value: *x,
+            });
+        }
+        Ok(())
+    }
+
+    /// Solves the LP (or MILP, if flows are integral) of all commodities at once.
+    /// Every commodity is balanced like a single one with free dummy nodes: if its
+    /// supply exceeds its demand, the demand is met and some supply is left over,
+    /// otherwise all supply is shipped and some demand is unmet
+    pub fn solve(&self) -> Result<MultiCommoditySolution, SolverError<f64>> {
+        self.validate()?;
+        let (m, n) = (
+            self.commodities[0].supply.len(),
+            self.commodities[0].demand.len(),
+        );
+
+        // Forbidden routes get no variables
+        let mut problem_variables = ProblemVariables::new();
+        let variables = self
+            .commodities
+            .iter()
+            .map(|commodity| {
+                let mut allowed = vec![vec![true; n]; m];
+                for (i, j) in commodity.forbidden.iter() {
+                    allowed[*i][*j] = false;
+                }
+                let capacities = commodity.capacity_grid();
+                (0..m)
+                    .map(|i| {
+                        (0..n)
+                            .map(|j| {
+                                allowed[i][j].then(|| {
+                                    let definition = variable().min(0);
+                                    let definition = match capacities[i][j] {
+                                        Some(capacity) => definition.max(capacity),
+                                        None => definition,
+                                    };
+                                    problem_variables.add(match self.integral {
+                                        true => definition.integer(),
+                                        false => definition,
+                                    })
+                                })
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let cost = |k: usize| -> Expression {
+            (0..m)
+                .flat_map(|i| (0..n).map(move |j| (i, j)))
+                .filter_map(|(i, j)| {
+                    variables[k][i][j].map(|x| self.commodities[k].costs[i][j] * x)
+                })
+                .sum()
+        };
+        let objective = (0..self.commodities.len()).map(cost).sum::<Expression>();
+        let mut model = problem_variables.minimise(objective).using(default_solver);
+
+        // Disable model output
+        model.set_parameter("log", "0");
+
+        for (k, commodity) in self.commodities.iter().enumerate() {
+            let surplus = commodity
+                .total_supply()
+                .compare(&commodity.total_demand())
+                .is_gt();
+            for (row, supply) in variables[k].iter().zip(commodity.supply.iter()) {
+                let shipped = row.iter().flatten().sum::<Expression>();
+                model = model.with(match surplus {
+                    true => constraint!(shipped <= *supply),
+                    false => constraint!(shipped == *supply),
+                });
+            }
+            for (j, demand) in commodity.demand.iter().enumerate() {
+                let received = variables[k]
+                    .iter()
+                    .filter_map(|row| row[j])
+                    .sum::<Expression>();
+                model = model.with(match surplus {
+                    true => constraint!(received == *demand),
+                    false => constraint!(received <= *demand),
+                });
+            }
+        }
+
+        let mut shared = vec![vec![None; n]; m];
+        for (i, j, x) in self.capacities.iter() {
+            shared[*i][*j] = Some(shared[*i][*j].map_or(*x, |y: f64| y.lesser(*x)));
+        }
+        for (i, row) in shared.iter().enumerate() {
+            for (j, capacity) in row.iter().enumerate() {
+                if let Some(capacity) = capacity {
+                    let total = variables.iter().filter_map(|x| x[i][j]).sum::<Expression>();
+                    model = model.with(constraint!(total <= *capacity));
+                }
+            }
+        }
+
+        // Flows are bounded, so the model can only be infeasible
+        let solution = model.solve().map_err(|_| SolverError::Infeasible)?;
+
+        let flows = variables
+            .iter()
+            .map(|grid| {
+                grid.iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|x| x.map_or(0.0, |x| solution.value(x)))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let costs = flows
+            .iter()
+            .zip(self.commodities.iter())
+            .map(|(grid, commodity)| {
+                grid.iter()
+                    .flatten()
+                    .zip(commodity.costs.iter().flatten())
+                    .map(|(flow, cost)| flow * cost)
+                    .sum::<f64>()
+            })
+            .collect::<Vec<_>>();
+        let unmet_demand = flows
+            .iter()
+            .zip(self.commodities.iter())
+            .map(|(grid, commodity)| {
+                (0..n)
+                    .map(|j| commodity.demand[j] - grid.iter().map(|row| row[j]).sum::<f64>())
+                    .collect()
+            })
+            .collect();
+        let leftover_supply = flows
+            .iter()
+            .zip(self.commodities.iter())
+            .map(|(grid, commodity)| {
+                (0..m)
+                    .map(|i| commodity.supply[i] - grid[i].iter().sum::<f64>())
+                    .collect()
+            })
+            .collect();
+
+        Ok(MultiCommoditySolution {
+            flows,
+            objective: costs.iter().sum(),
+            costs,
+            unmet_demand,
+            leftover_supply,
+        })
+    }
+}
@@ -0,0 +1,164 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::iter::Sum;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use serde::{Deserialize, Serialize};
+
+use crate::number::Number;
+
+/// Cost of a route, where forbidden routes are more expensive than any allowed
+/// one. It is an exact big-M: `big` counts the M's and is compared first, so
+/// nothing overflows and no allowed cost can be mistaken for a forbidden one
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Cost<T = i32> {
+    pub big: T,
+    pub val: T,
+}
+
+impl<T: Number> Cost<T> {
+    pub fn new(val: T) -> Self {
+        Self {
+            big: T::zero(),
+            val,
+        }
+    }
+
+    pub fn forbidden() -> Self {
+        Self {
+            big: T::from_i32(1),
+            val: T::zero(),
+        }
+    }
+
+    pub fn is_forbidden(&self) -> bool {
+        !self.big.is_zero()
+    }
+
+    /// Compares within the tolerance of the number type. Optimality tests use it,
+    /// while `Ord` stays exact, so that sorting is consistent
+    pub fn compare(&self, other: &Self) -> Ordering {
+        self.big
+            .compare(&other.big)
+            .then_with(|| self.val.compare(&other.val))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.compare(&Cost::default()).is_eq()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.compare(&Cost::default()).is_lt()
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.compare(&Cost::default()).is_gt()
+    }
+
+    /// Sum, or `None` if any part overflows
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(Cost {
+            big: self.big.checked_add(rhs.big)?,
+            val: self.val.checked_add(rhs.val)?,
+        })
+    }
+
+    /// Product, or `None` if any part overflows
+    pub fn checked_mul(self, rhs: T) -> Option<Self> {
+        Some(Cost {
+            big: self.big.checked_mul(rhs)?,
+            val: self.val.checked_mul(rhs)?,
+        })
+    }
+}
+
+impl<T: Number> PartialEq for Cost<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl<T: Number> Eq for Cost<T> {}
+
+impl<T: Number> PartialOrd for Cost<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Number> Ord for Cost<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.big
+            .total_cmp(&other.big)
+            .then_with(|| self.val.total_cmp(&other.val))
+    }
+}
+
+impl<T: Number> Add for Cost<T> {
+    type Output = Cost<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Cost {
+            big: self.big + rhs.big,
+            val: self.val + rhs.val,
+        }
+    }
+}
+
+impl<T: Number> Sub for Cost<T> {
+    type Output = Cost<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Cost {
+            big: self.big - rhs.big,
+            val: self.val - rhs.val,
+        }
+    }
+}
+
+impl<T: Number> Neg for Cost<T> {
+    type Output = Cost<T>;
+
+    fn neg(self) -> Self::Output {
+        Cost {
+            big: -self.big,
+            val: -self.val,
+        }
+    }
+}
+
+impl<T: Number> Mul<T> for Cost<T> {
+    type Output = Cost<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Cost {
+            big: self.big * rhs,
+            val: self.val * rhs,
+        }
+    }
+}
+
+impl<T: Number> Sum for Cost<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Cost::default(), |acc, x| acc + x)
+    }
+}
+
+/// Written as a multiple of M plus the value, like `2M-3`
+impl<T: Number> Display for Cost<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.big.is_zero() {
+            return write!(f, "{}", self.val);
+        }
+        match self.big.compare(&T::from_i32(1)).is_eq() {
+            true => write!(f, "M")?,
+            false if self.big.compare(&T::from_i32(-1)).is_eq() => write!(f, "-M")?,
+            false => write!(f, "{}M", self.big)?,
+        }
+        match self.val.compare(&T::zero()) {
+            Ordering::Less => write!(f, "-{}", -self.val),
+            Ordering::Equal => Ok(()),
+            Ordering::Greater => write!(f, "+{}", self.val),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolverError<T = i32> {
+    /// Problem has no sources or no destinations
+    Empty,
+    /// Length of some data doesn't match the number of sources or destinations
+    DimensionMismatch {
+        what: String,
+        expected: usize,
+        found: usize,
+    },
+    /// Forbidden route refers to a nonexistent source or destination
+    RouteOutOfBounds {
+        source: usize,
+        destination: usize,
+    },
+    /// Lane of a sparse problem is listed more than once
+    DuplicateRoute {
+        source: usize,
+        destination: usize,
+    },
+    NegativeSupply {
+        source: usize,
+        value: T,
+    },
+    NegativeDemand {
+        destination: usize,
+        value: T,
+    },
+    NegativeCapacity {
+        source: usize,
+        destination: usize,
+        value: T,
+    },
+    /// Total supply differs from total demand, while balancing is disabled
+    Unbalanced {
+        supply: T,
+        demand: T,
+    },
+    /// Arc of a transshipment network leads into a source, out of a sink,
+    /// from a hub to itself or to a nonexistent node
+    InvalidArc {
+        arc: usize,
+    },
+    /// Change of a solved problem refers to a nonexistent route, source or destination
+    ChangeOutOfBounds {
+        change: usize,
+    },
+    /// Input file can't be read as a problem
+    Parse {
+        line: u64,
+        message: String,
+    },
+    /// Demand can't be satisfied without forbidden routes or exceeding capacities
+    Infeasible,
+    /// Optimum was not reached within the allowed number of iterations
+    IterationLimit {
+        iterations: usize,
+    },
+    /// Objective doesn't fit into the numeric type
+    Overflow,
+}
+
+impl<T: Display> Display for SolverError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::Empty => write!(f, "Problem has no sources or destinations"),
+            SolverError::DimensionMismatch {
+                what,
+                expected,
+                found,
+            } => write!(f, "Expected {expected} {what}, found {found}"),
+            SolverError::RouteOutOfBounds {
+                source,
+                destination,
+            } => write!(
+                f,
+                "Route from source {source} to destination {destination} is out of bounds"
+            ),
+            SolverError::DuplicateRoute {
+                source,
+                destination,
+            } => write!(
+                f,
+                "Route from source {source} to destination {destination} is listed more than once"
+            ),
+            SolverError::NegativeSupply { source, value } => {
+                write!(f, "Supply of source {source} is negative: {value}")
+            }
+            SolverError::NegativeDemand { destination, value } => {
+                write!(
+                    f,
+                    "Demand of destination {destination} is negative: {value}"
+                )
+            }
+            SolverError::NegativeCapacity {
+                source,
+                destination,
+                value,
+            } => write!(
+                f,
+                "Capacity of route from source {source} to destination {destination} is negative: {value}"
+            ),
+            SolverError::Unbalanced { supply, demand } => {
+                write!(
+                    f,
+                    "Total supply {supply} differs from total demand {demand}"
+                )
+            }
+            SolverError::InvalidArc { arc } => {
+                write!(
+                    f,
+                    "Arc {arc} doesn't lead from a source or hub to a hub or sink"
+                )
+            }
+            SolverError::ChangeOutOfBounds { change } => {
+                write!(
+                    f,
+                    "Change {change} refers to a nonexistent route, source or destination"
+                )
+            }
+            SolverError::Parse { line, message } => write!(f, "Line {line}: {message}"),
+            SolverError::Infeasible => {
+                write!(
+                    f,
+                    "Demand can't be satisfied without forbidden routes or exceeding capacities"
+                )
+            }
+            SolverError::IterationLimit { iterations } => {
+                write!(f, "Optimum was not reached in {iterations} iterations")
+            }
+            SolverError::Overflow => write!(f, "Objective overflows the numeric type"),
+        }
+    }
+}
+
+impl<T: Debug + Display> Error for SolverError<T> {}
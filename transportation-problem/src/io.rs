@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use csv::{Position, ReaderBuilder, StringRecord, Trim, WriterBuilder};
+
+use crate::error::SolverError;
+use crate::number::Number;
+use crate::problem::{Named, Problem};
+use crate::solution::Solution;
+
+/// Cells marking a forbidden route in a cost matrix
+const FORBIDDEN: [&str; 3] = ["", "-", "M"];
+
+/// Non-empty records of the CSV text along with their line numbers
+fn records<T>(text: &str) -> Result<Vec<(u64, StringRecord)>, SolverError<T>> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(Trim::All)
+        .from_reader(text.as_bytes());
+    // Reader doesn't count blank lines, and its offsets point before them
+    let line = |position: Option<&Position>| {
+        let offset = position.map_or(0, |x| x.byte() as usize).min(text.len());
+        let blank = text[offset..].len() - text[offset..].trim_start_matches(['\r', '\n']).len();
+        text[..offset + blank].matches('\n').count() as u64 + 1
+    };
+    let mut records = vec![];
+    for record in reader.records() {
+        let record = record.map_err(|error| SolverError::Parse {
+            line: line(error.position()),
+            message: error.to_string(),
+        })?;
+        if record.iter().any(|cell| !cell.is_empty()) {
+            records.push((line(record.position()), record));
+        }
+    }
+    Ok(records)
+}
+
+fn parse<T: Number + FromStr>(line: u64, cell: &str) -> Result<T, SolverError<T>> {
+    cell.parse().map_err(|_| SolverError::Parse {
+        line,
+        message: format!("Invalid number \"{cell}\""),
+    })
+}
+
+fn write<T: ToString>(rows: impl IntoIterator<Item = Vec<T>>) -> String {
+    let mut writer = WriterBuilder::new().flexible(true).from_writer(vec![]);
+    for row in rows {
+        writer
+            .write_record(row.iter().map(|x| x.to_string()))
+            .expect("Writing to memory can't fail");
+    }
+    String::from_utf8(writer.into_inner().expect("Writing to memory can't fail"))
+        .expect("Records are valid UTF-8")
+}
+
+/// Reads a cost matrix with names of destinations in the header, names of sources
+/// in the first column, supplies in the last column labeled `Supply` and demands
+/// in the last row labeled `Demand`:
+///
+/// ```text
+/// ,Berlin,Paris,Supply
+/// Hamburg,4,8,76
+/// Lyon,M,3,82
+/// Demand,72,86
+/// ```
+///
+/// Routes with empty, `-` or `M` costs are forbidden
+pub fn read_matrix<T: Number + FromStr>(text: &str) -> Result<Problem<T>, SolverError<T>> {
+    let records = records(text)?;
+    let Some(((header_line, header), rest)) = records.split_first() else {
+        return Err(SolverError::Empty);
+    };
+    if header.len() < 2 || !header[header.len() - 1].eq_ignore_ascii_case("supply") {
+        return Err(SolverError::Parse {
+            line: *header_line,
+            message: "Expected a Supply column after the destinations".to_string(),
+        });
+    }
+    let last = rest.split_last();
+    let Some(((demand_line, demand), rows)) =
+        last.filter(|((_, x), _)| x[0].eq_ignore_ascii_case("demand"))
+    else {
+        return Err(SolverError::Parse {
+            line: last.map_or(*header_line, |((line, _), _)| *line),
+            message: "Expected a Demand row after the sources".to_string(),
+        });
+    };
+    let n = header.len() - 2;
+    let mut problem = Problem {
+        destinations: header.iter().skip(1).take(n).map(String::from).collect(),
+        ..Default::default()
+    };
+
+    for (line, row) in rows.iter() {
+        if row.len() != n + 2 {
+            return Err(SolverError::Parse {
+                line: *line,
+                message: format!("Expected {} cells, found {}", n + 2, row.len()),
+            });
+        }
+        let i = problem.supply.len();
+        let mut costs = Vec::with_capacity(n);
+        for (j, cell) in row.iter().skip(1).take(n).enumerate() {
+            match FORBIDDEN.contains(&cell) {
+                true => {
+                    problem.forbidden.push((i, j));
+                    costs.push(T::zero());
+                }
+                false => costs.push(parse(*line, cell)?),
+            }
+        }
+        problem.costs.push(costs);
+        problem.sources.push(row[0].to_string());
+        problem.supply.push(parse(*line, &row[n + 1])?);
+    }
+
+    // Total supply may follow the demands
+    if !(n + 1..=n + 2).contains(&demand.len()) {
+        return Err(SolverError::Parse {
+            line: *demand_line,
+            message: format!("Expected {} demands", n),
+        });
+    }
+    problem.demand = demand
+        .iter()
+        .skip(1)
+        .take(n)
+        .map(|cell| parse(*demand_line, cell))
+        .collect::<Result<_, _>>()?;
+    Ok(problem)
+}
+
+/// Writes the problem as a cost matrix, which `read_matrix` reads back.
+/// Capacities are not a part of this layout
+pub fn write_matrix<T: Number>(problem: &Problem<T>) -> String {
+    let (m, n) = (problem.supply.len(), problem.demand.len());
+    let costs = problem.weights();
+    let mut header = vec![String::new()];
+    header.extend((0..n).map(|j| problem.destination_name(j)));
+    header.push("Supply".to_string());
+
+    let rows = (0..m).map(|i| {
+        let mut row = vec![problem.source_name(i)];
+        row.extend((0..n).map(|j| match costs[i][j].is_forbidden() {
+            true => "M".to_string(),
+            false => problem.costs[i][j].to_string(),
+        }));
+        row.push(problem.supply[i].to_string());
+        row
+    });
+    let mut demand = vec!["Demand".to_string()];
+    demand.extend(problem.demand.iter().map(|x| x.to_string()));
+
+    write([header].into_iter().chain(rows).chain([demand]))
+}
+
+/// Names with amounts in the order of the file, after a header row
+fn read_amounts<T: Number + FromStr>(
+    text: &str,
+    what: &str,
+) -> Result<(Vec<String>, Vec<T>), SolverError<T>> {
+    let (mut names, mut amounts) = (vec![], vec![]);
+    for (line, row) in records(text)?.into_iter().skip(1) {
+        if row.len() != 2 {
+            return Err(SolverError::Parse {
+                line,
+                message: format!("Expected a name and a {what}"),
+            });
+        }
+        if names.iter().any(|x| *x == row[0]) {
+            return Err(SolverError::Parse {
+                line,
+                message: format!("Duplicate name \"{}\"", &row[0]),
+            });
+        }
+        names.push(row[0].to_string());
+        amounts.push(parse(line, &row[1])?);
+    }
+    Ok((names, amounts))
+}
+
+/// Reads an edge list `from,to,cost[,capacity]` with the supplies `name,supply`
+/// and demands `name,demand`, all with a header row. Sources and destinations
+/// are ordered as in their files, and routes, which are not listed, are forbidden
+pub fn read_edges<T: Number + FromStr>(
+    edges: &str,
+    supply: &str,
+    demand: &str,
+) -> Result<Problem<T>, SolverError<T>> {
+    let (sources, supply) = read_amounts(supply, "supply")?;
+    let (destinations, demand) = read_amounts(demand, "demand")?;
+    let (m, n) = (sources.len(), destinations.len());
+    let index = |names: &[String]| {
+        names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (name.clone(), idx))
+            .collect::<HashMap<_, _>>()
+    };
+    let (rows, cols) = (index(&sources), index(&destinations));
+
+    let mut problem = Problem {
+        costs: vec![vec![T::zero(); n]; m],
+        supply,
+        demand,
+        sources,
+        destinations,
+        ..Default::default()
+    };
+    let mut listed = vec![vec![false; n]; m];
+    for (line, edge) in records(edges)?.into_iter().skip(1) {
+        let error = |message: String| SolverError::Parse { line, message };
+        if !(3..=4).contains(&edge.len()) {
+            return Err(error("Expected from, to, cost and capacity".to_string()));
+        }
+        let i = *rows
+            .get(&edge[0])
+            .ok_or_else(|| error(format!("Unknown source \"{}\"", &edge[0])))?;
+        let j = *cols
+            .get(&edge[1])
+            .ok_or_else(|| error(format!("Unknown destination \"{}\"", &edge[1])))?;
+        if listed[i][j] {
+            return Err(error(format!(
+                "Duplicate route from \"{}\" to \"{}\"",
+                &edge[0], &edge[1]
+            )));
+        }
+        listed[i][j] = true;
+        problem.costs[i][j] = parse(line, &edge[2])?;
+        if let Some(capacity) = edge.get(3).filter(|x| !x.is_empty()) {
+            problem.capacities.push((i, j, parse(line, capacity)?));
+        }
+    }
+    problem.forbidden = (0..m)
+        .flat_map(|i| (0..n).map(move |j| (i, j)))
+        .filter(|(i, j)| !listed[*i][*j])
+        .collect();
+    Ok(problem)
+}
+
+/// Writes allowed routes as an edge list, which `read_edges` reads back
+pub fn write_edges<T: Number>(problem: &Problem<T>) -> String {
+    let costs = problem.weights();
+    let capacities = problem.capacity_grid();
+    let header = ["from", "to", "cost", "capacity"]
+        .map(String::from)
+        .to_vec();
+    let edges = (0..problem.supply.len())
+        .flat_map(|i| (0..problem.demand.len()).map(move |j| (i, j)))
+        .filter(|(i, j)| !costs[*i][*j].is_forbidden())
+        .map(|(i, j)| {
+            vec![
+                problem.source_name(i),
+                problem.destination_name(j),
+                problem.costs[i][j].to_string(),
+                capacities[i][j].map_or(String::new(), |x| x.to_string()),
+            ]
+        });
+    write([header].into_iter().chain(edges))
+}
+
+/// Writes supplies of sources as `name,supply`
+pub fn write_supply<T: Number>(problem: &Problem<T>) -> String {
+    let rows = problem
+        .supply
+        .iter()
+        .enumerate()
+        .map(|(i, x)| vec![problem.source_name(i), x.to_string()]);
+    write(
+        [vec!["name".to_string(), "supply".to_string()]]
+            .into_iter()
+            .chain(rows),
+    )
+}
+
+/// Writes demands of destinations as `name,demand`
+pub fn write_demand<T: Number>(problem: &Problem<T>) -> String {
+    let rows = problem
+        .demand
+        .iter()
+        .enumerate()
+        .map(|(j, x)| vec![problem.destination_name(j), x.to_string()]);
+    write(
+        [vec!["name".to_string(), "demand".to_string()]]
+            .into_iter()
+            .chain(rows),
+    )
+}
+
+/// Writes routes carrying goods as `from,to,amount`
+pub fn write_plan<T: Number>(solution: &Solution<T>) -> String {
+    let header = ["from", "to", "amount"].map(String::from).to_vec();
+    let routes = solution.routes().map(|route| {
+        vec![
+            solution.source_name(route.source),
+            solution.destination_name(route.destination),
+            route.amount.to_string(),
+        ]
+    });
+    write([header].into_iter().chain(routes))
+}
@@ -1,5 +1,18 @@
+pub mod assignment;
+pub mod certificate;
+#[cfg(feature = "multi-commodity")]
+pub mod commodity;
+pub mod cost;
+pub mod error;
+pub mod io;
+pub mod number;
 pub mod problem;
+pub mod render;
+pub mod solution;
 pub mod solver;
+pub mod sparse;
+pub mod transshipment;
+mod utils;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,159 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use num_rational::Rational64;
+use num_traits::{CheckedAdd, CheckedMul};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Relative tolerance of floating point comparisons
+const EPSILON: f64 = 1e-9;
+
+/// Numeric type of costs and quantities
+pub trait Number:
+    Copy
+    + Debug
+    + Display
+    + Default
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + Sum
+    + Serialize
+    + DeserializeOwned
+{
+    fn from_i32(value: i32) -> Self;
+
+    /// Values within the tolerance of the type are equal
+    fn compare(&self, other: &Self) -> Ordering;
+
+    /// Exact total order. Unlike `compare` it is transitive, so it is safe for sorting
+    fn total_cmp(&self, other: &Self) -> Ordering;
+
+    /// Sum, or `None` if it overflows
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// Product, or `None` if it overflows
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.compare(&Self::zero()).is_eq()
+    }
+
+    fn is_negative(&self) -> bool {
+        self.compare(&Self::zero()).is_lt()
+    }
+
+    fn is_positive(&self) -> bool {
+        self.compare(&Self::zero()).is_gt()
+    }
+
+    fn lesser(self, other: Self) -> Self {
+        match other.compare(&self) {
+            Ordering::Less => other,
+            _ => self,
+        }
+    }
+}
+
+impl Number for i32 {
+    fn from_i32(value: i32) -> Self {
+        value
+    }
+
+    fn compare(&self, other: &Self) -> Ordering {
+        self.cmp(other)
+    }
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        self.cmp(other)
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        i32::checked_add(self, other)
+    }
+
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        i32::checked_mul(self, other)
+    }
+}
+
+impl Number for i64 {
+    fn from_i32(value: i32) -> Self {
+        value as i64
+    }
+
+    fn compare(&self, other: &Self) -> Ordering {
+        self.cmp(other)
+    }
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        self.cmp(other)
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        i64::checked_add(self, other)
+    }
+
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        i64::checked_mul(self, other)
+    }
+}
+
+impl Number for f64 {
+    fn from_i32(value: i32) -> Self {
+        value as f64
+    }
+
+    fn compare(&self, other: &Self) -> Ordering {
+        let scale = self.abs().max(other.abs()).max(1.0);
+        match (self - other).abs() <= EPSILON * scale {
+            true => Ordering::Equal,
+            false => self.total_cmp(other),
+        }
+    }
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f64::total_cmp(self, other)
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Some(self + other).filter(|x| x.is_finite())
+    }
+
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        Some(self * other).filter(|x| x.is_finite())
+    }
+}
+
+impl Number for Rational64 {
+    fn from_i32(value: i32) -> Self {
+        Rational64::from_integer(value as i64)
+    }
+
+    fn compare(&self, other: &Self) -> Ordering {
+        self.cmp(other)
+    }
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        self.cmp(other)
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        CheckedAdd::checked_add(&self, &other)
+    }
+
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        CheckedMul::checked_mul(&self, &other)
+    }
+}
@@ -1,21 +1,231 @@
+use crate::cost::Cost;
+use crate::error::SolverError;
+use crate::number::Number;
+
 use rand::distributions::Uniform;
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 
+/// Cost large enough to keep a route unused, unless there is no other way.
+/// The objective overflows easily, while forbidden routes are exact
+#[deprecated(note = "list the route in `Problem::forbidden` instead")]
 pub const M: i32 = 5_000_000;
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Problem<T = i32> {
+    pub costs: Vec<Vec<T>>,
+    pub supply: Vec<T>,
+    pub demand: Vec<T>,
+    /// Routes (source, destination), which can't be used. Their costs are ignored
+    #[serde(default)]
+    pub forbidden: Vec<(usize, usize)>,
+    /// Routes (source, destination, capacity), which can't carry more goods than
+    /// the capacity. If a route is listed several times, the smallest one applies
+    #[serde(default)]
+    pub capacities: Vec<(usize, usize, T)>,
+    /// Names of sources, empty if they are unnamed
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// Names of destinations, empty if they are unnamed
+    #[serde(default)]
+    pub destinations: Vec<String>,
+}
+
+/// Problem or its solution, which may name its sources and destinations
+pub trait Named {
+    fn sources(&self) -> &[String];
+    fn destinations(&self) -> &[String];
+
+    /// Name of the source, or `S1`, `S2` and so on if it is unnamed
+    fn source_name(&self, i: usize) -> String {
+        self.sources()
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| format!("S{}", i + 1))
+    }
+
+    /// Name of the destination, or `D1`, `D2` and so on if it is unnamed
+    fn destination_name(&self, j: usize) -> String {
+        self.destinations()
+            .get(j)
+            .cloned()
+            .unwrap_or_else(|| format!("D{}", j + 1))
+    }
+}
+
+impl<T> Named for Problem<T> {
+    fn sources(&self) -> &[String] {
+        &self.sources
+    }
+
+    fn destinations(&self) -> &[String] {
+        &self.destinations
+    }
+}
+
+/// Per-unit costs charged by the dummy node when the problem is unbalanced.
+/// Nodes without an entry are not penalized, so the default is free.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Penalties<T = i32> {
+    /// Cost of a unit of demand left unmet, per destination
+    pub shortage: Vec<T>,
+    /// Cost of a unit of supply left over, per source
+    pub surplus: Vec<T>,
+}
+
+/// Treatment of problems, where total supply differs from total demand
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Problem {
-    pub costs: Vec<Vec<i32>>,
-    pub supply: Vec<i32>,
-    pub demand: Vec<i32>,
+pub enum Balancing<T = i32> {
+    /// Such problems are rejected
+    Strict,
+    /// Difference is taken by a dummy node charging the penalties
+    Dummy(Penalties<T>),
+}
+
+impl<T> Default for Balancing<T> {
+    fn default() -> Self {
+        Balancing::Dummy(Penalties {
+            shortage: vec![],
+            surplus: vec![],
+        })
+    }
 }
 
+/// Node added to balance the total supply and demand
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Dummy {
+    /// Extra source, its shipments are the unmet demand
+    Source,
+    /// Extra destination, its shipments are the leftover supply
+    Destination,
+}
+
+impl Problem {
+    /// Same problem with costs and quantities of another numeric type
+    pub fn convert<T: Number>(&self) -> Problem<T> {
+        let convert = |x: &Vec<i32>| x.iter().map(|x| T::from_i32(*x)).collect::<Vec<_>>();
+        Problem {
+            costs: self.costs.iter().map(convert).collect(),
+            supply: convert(&self.supply),
+            demand: convert(&self.demand),
+            forbidden: self.forbidden.clone(),
+            capacities: self
+                .capacities
+                .iter()
+                .map(|(i, j, x)| (*i, *j, T::from_i32(*x)))
+                .collect(),
+            sources: self.sources.clone(),
+            destinations: self.destinations.clone(),
+        }
+    }
+}
+
+impl<T: Number> Problem<T> {
+    /// Checks that the data is consistent, so that the problem can be solved.
+    /// Apart from the shape of the costs, it is checked as a sparse problem
+    pub fn validate(&self) -> Result<(), SolverError<T>> {
+        self.validate_shape()?;
+        self.sparse().validate()
+    }
+
+    /// Checks that costs and routes fit the numbers of sources and destinations,
+    /// so that the problem can be made sparse
+    pub(crate) fn validate_shape(&self) -> Result<(), SolverError<T>> {
+        let (m, n) = (self.supply.len(), self.demand.len());
+        if self.costs.len() != m {
+            return Err(SolverError::DimensionMismatch {
+                what: "cost rows".to_string(),
+                expected: m,
+                found: self.costs.len(),
+            });
+        }
+        if let Some(row) = self.costs.iter().find(|row| row.len() != n) {
+            return Err(SolverError::DimensionMismatch {
+                what: "cost columns".to_string(),
+                expected: n,
+                found: row.len(),
+            });
+        }
+        if let Some((source, destination)) = self
+            .forbidden
+            .iter()
+            .copied()
+            .chain(self.capacities.iter().map(|(i, j, _)| (*i, *j)))
+            .find(|(i, j)| *i >= m || *j >= n)
+        {
+            return Err(SolverError::RouteOutOfBounds {
+                source,
+                destination,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn total_supply(&self) -> T {
+        self.supply.iter().copied().sum()
+    }
+
+    pub fn total_demand(&self) -> T {
+        self.demand.iter().copied().sum()
+    }
+
+    /// Costs of all routes, where the forbidden ones are priced above
+    /// any allowed route
+    pub fn weights(&self) -> Vec<Vec<Cost<T>>> {
+        let mut weights = self
+            .costs
+            .iter()
+            .map(|row| row.iter().map(|x| Cost::new(*x)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for (i, j) in self.forbidden.iter() {
+            weights[*i][*j] = Cost::forbidden();
+        }
+        weights
+    }
+
+    /// Capacity of every route, `None` if it is unbounded
+    pub fn capacity_grid(&self) -> Vec<Vec<Option<T>>> {
+        let mut grid = vec![vec![None; self.demand.len()]; self.supply.len()];
+        for (i, j, x) in self.capacities.iter() {
+            grid[*i][*j] = Some(grid[*i][*j].map_or(*x, |y: T| y.lesser(*x)));
+        }
+        grid
+    }
+}
+
+/// Structure of generated costs
+#[derive(Clone, Debug, Default)]
+pub enum Family {
+    /// Independent costs, some rows and columns of which are free,
+    /// while some cells are free or blocked
+    #[default]
+    Uniform,
+    /// Distances between random points of a unit square
+    Geometric,
+    /// Distances between points grouped around the given number of centers.
+    /// Routes between groups cost `max_value` more
+    Clustered { clusters: usize },
+    /// Balanced problems, where most demands end exactly where supplies do,
+    /// so the north-west corner solution and the simplex steps are degenerate.
+    /// `demand_ratio` is ignored
+    Degenerate,
+}
+
+#[derive(Clone, Debug)]
 pub struct GenConfig {
     pub max_value: i32,
     pub m_val_probability: f32,
     pub zero_val_probability: f32,
     pub zero_col_row_max_fraction: f32,
+    /// Blocked cells of uniform costs are forbidden routes, so they aren't
+    /// stored by sparse problems. Otherwise they cost the deprecated `M`
+    pub forbid_m_vals: bool,
+    /// Every problem is generated from this seed, so they are all the same.
+    /// A random one is used if `None`
+    pub seed: Option<u64>,
+    /// Total demand divided by total supply, except for degenerate problems
+    pub demand_ratio: f32,
+    pub family: Family,
 }
 
 impl Default for GenConfig {
@@ -25,47 +235,149 @@ impl Default for GenConfig {
             m_val_probability: 0.1,
             zero_val_probability: 0.02,
             zero_col_row_max_fraction: 0.2,
+            forbid_m_vals: true,
+            seed: None,
+            demand_ratio: 1.0,
+            family: Family::Uniform,
         }
     }
 }
 
 impl GenConfig {
     pub fn gen(&self, n: usize) -> Problem {
-        let mut rng = thread_rng();
+        self.gen_rect(n, n)
+    }
+
+    /// Problem with `m` sources and `n` destinations
+    pub fn gen_rect(&self, m: usize, n: usize) -> Problem {
+        match self.seed {
+            Some(seed) => self.gen_with(&mut StdRng::seed_from_u64(seed), m, n),
+            None => self.gen_with(&mut thread_rng(), m, n),
+        }
+    }
 
+    /// Problem with `m` sources and `n` destinations drawn from the generator,
+    /// so that a seeded one gives a repeatable series of problems
+    pub fn gen_with<R: Rng>(&self, rng: &mut R, m: usize, n: usize) -> Problem {
         // Supply
         let dist = Uniform::new_inclusive(1, self.max_value);
-        let mut supply = Vec::with_capacity(n);
-        let mut total_supply = 0;
-        let mut temp;
-        for _ in 0..n {
-            temp = dist.sample(&mut rng);
-            supply.push(temp);
-            total_supply += temp;
-        }
+        let supply = (0..m).map(|_| dist.sample(rng)).collect::<Vec<_>>();
+        let total_supply = supply.iter().sum::<i32>();
 
         // Demand
-        let mut demand_f = Vec::with_capacity(n);
-        let mut total_demand_f = 0.0;
-        let mut temp;
-        for _ in 0..n {
-            temp = rng.gen::<f32>();
-            demand_f.push(temp);
-            total_demand_f += temp;
+        let demand = match self.family {
+            Family::Degenerate => Self::aligned_demand(rng, &supply, n),
+            _ => {
+                let total = (total_supply as f32 * self.demand_ratio).round() as i32;
+                Self::split_demand(rng, total, n)
+            }
+        };
+
+        // Costs
+        let (costs, forbidden) = match self.family {
+            Family::Uniform => self.uniform_costs(rng, m, n),
+            Family::Geometric => {
+                let sources = Self::points(rng, m);
+                let destinations = Self::points(rng, n);
+                (self.distances(&sources, &destinations), vec![])
+            }
+            Family::Clustered { clusters } => {
+                let centers = Self::points(rng, clusters.max(1));
+                let (rows, sources) = Self::clustered(rng, &centers, m);
+                let (cols, destinations) = Self::clustered(rng, &centers, n);
+                let mut costs = self.distances(&sources, &destinations);
+                for (i, row) in costs.iter_mut().enumerate() {
+                    for (j, cost) in row.iter_mut().enumerate() {
+                        if rows[i] != cols[j] {
+                            *cost += self.max_value;
+                        }
+                    }
+                }
+                (costs, vec![])
+            }
+            Family::Degenerate => (
+                (0..m)
+                    .map(|_| (0..n).map(|_| dist.sample(rng)).collect())
+                    .collect(),
+                vec![],
+            ),
+        };
+
+        // Verify
+        debug_assert!(
+            supply.iter().fold(true, |acc, x| acc & (x >= &0)),
+            "Non-negativity constraint is violated for supply"
+        );
+        debug_assert!(
+            demand.iter().fold(true, |acc, x| acc & (x >= &0)),
+            "Non-negativity constraint is violated for demand"
+        );
+
+        Problem {
+            costs,
+            supply,
+            demand,
+            forbidden,
+            ..Default::default()
         }
+    }
+
+    /// Splits the total into random shares
+    fn split_demand<R: Rng>(rng: &mut R, total: i32, n: usize) -> Vec<i32> {
+        let demand_f = (0..n).map(|_| rng.gen::<f32>()).collect::<Vec<_>>();
+        let total_demand_f = demand_f.iter().sum::<f32>();
         let mut demand = Vec::with_capacity(n);
         let mut total_demand = 0;
         let mut temp;
         for d in demand_f[..(n - 1)].iter() {
-            temp = (d / total_demand_f * total_supply as f32) as i32;
+            temp = (d / total_demand_f * total as f32) as i32;
             demand.push(temp);
             total_demand += temp;
         }
-        demand.push(total_supply - total_demand);
+        demand.push(total - total_demand);
+        demand
+    }
 
-        // Costs
-        let mut costs = vec![vec![0; n]; n];
-        let rows = n - (rng.gen_range(0.0..self.zero_col_row_max_fraction) * (n as f32)) as usize;
+    /// Demands, which end where supplies end, as far as there are enough supplies
+    fn aligned_demand<R: Rng>(rng: &mut R, supply: &[i32], n: usize) -> Vec<i32> {
+        let ends = supply[..supply.len() - 1]
+            .iter()
+            .scan(0, |acc, x| {
+                *acc += x;
+                Some(*acc)
+            })
+            .collect::<Vec<_>>();
+        let total = supply.iter().sum::<i32>();
+        let mut cuts = ends
+            .choose_multiple(rng, ends.len().min(n - 1))
+            .copied()
+            .collect::<Vec<_>>();
+        while cuts.len() < n - 1 {
+            cuts.push(rng.gen_range(0..=total));
+        }
+        cuts.sort();
+        cuts.push(total);
+        let mut prev = 0;
+        cuts.into_iter()
+            .map(|x| {
+                let demand = x - prev;
+                prev = x;
+                demand
+            })
+            .collect()
+    }
+
+    /// Costs along with the forbidden routes, if blocked routes aren't priced `M`
+    #[allow(deprecated)]
+    fn uniform_costs<R: Rng>(
+        &self,
+        rng: &mut R,
+        m: usize,
+        n: usize,
+    ) -> (Vec<Vec<i32>>, Vec<(usize, usize)>) {
+        let mut costs = vec![vec![0; n]; m];
+        let mut forbidden = vec![];
+        let rows = m - (rng.gen_range(0.0..self.zero_col_row_max_fraction) * (m as f32)) as usize;
         let cols = n - (rng.gen_range(0.0..self.zero_col_row_max_fraction) * (n as f32)) as usize;
 
         #[allow(clippy::needless_range_loop)]
@@ -80,36 +392,61 @@ impl GenConfig {
             self.zero_val_probability..(self.zero_val_probability + self.m_val_probability);
 
         #[allow(clippy::needless_range_loop)]
-        for i in 0..n {
+        for i in 0..m {
             for j in 0..n {
                 let roll = rng.gen();
                 if zero_val_int.contains(&roll) {
                     costs[i][j] = 0
+                } else if m_val_int.contains(&roll) && self.forbid_m_vals {
+                    costs[i][j] = 0;
+                    forbidden.push((i, j));
                 } else if m_val_int.contains(&roll) {
                     costs[i][j] = M
                 }
             }
         }
+        (costs, forbidden)
+    }
 
-        // Verify
-        debug_assert_eq!(
-            supply.iter().sum::<i32>(),
-            demand.iter().sum::<i32>(),
-            "Not feasible"
-        );
-        debug_assert!(
-            supply.iter().fold(true, |acc, x| acc & (x >= &0)),
-            "Non-negativity constraint is violated for supply"
-        );
-        debug_assert!(
-            demand.iter().fold(true, |acc, x| acc & (x >= &0)),
-            "Non-negativity constraint is violated for demand"
-        );
+    /// Random points of the unit square
+    fn points<R: Rng>(rng: &mut R, count: usize) -> Vec<(f32, f32)> {
+        (0..count).map(|_| (rng.gen(), rng.gen())).collect()
+    }
 
-        Problem {
-            costs,
-            supply,
-            demand,
-        }
+    /// Indices of random centers along with points near them
+    fn clustered<R: Rng>(
+        rng: &mut R,
+        centers: &[(f32, f32)],
+        count: usize,
+    ) -> (Vec<usize>, Vec<(f32, f32)>) {
+        const SPREAD: f32 = 0.05;
+        (0..count)
+            .map(|_| {
+                let idx = rng.gen_range(0..centers.len());
+                let (x, y) = centers[idx];
+                let dx = rng.gen_range(-SPREAD..=SPREAD);
+                let dy = rng.gen_range(-SPREAD..=SPREAD);
+                (idx, (x + dx, y + dy))
+            })
+            .unzip()
+    }
+
+    /// Costs from 1 to `max_value` growing with the distance
+    fn distances(&self, sources: &[(f32, f32)], destinations: &[(f32, f32)]) -> Vec<Vec<i32>> {
+        // Diagonal of the square along with the spread of clusters on both ends
+        const LONGEST: f32 = 1.6;
+        sources
+            .iter()
+            .map(|(x1, y1)| {
+                destinations
+                    .iter()
+                    .map(|(x2, y2)| {
+                        let distance = ((x1 - x2).powi(2) + (y1 - y2).powi(2)).sqrt();
+                        let scaled = (distance / LONGEST).min(1.0) * (self.max_value - 1) as f32;
+                        1 + scaled.round() as i32
+                    })
+                    .collect()
+            })
+            .collect()
     }
 }
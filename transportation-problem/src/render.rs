@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use crate::cost::Cost;
+use crate::number::Number;
+use crate::solver::observer::Snapshot;
+
+/// Cell of a rendered tableau
+struct Entry {
+    text: String,
+    /// Basic cells are highlighted
+    basic: bool,
+}
+
+impl Entry {
+    fn new(text: impl ToString) -> Self {
+        Self {
+            text: text.to_string(),
+            basic: false,
+        }
+    }
+}
+
+/// Rows of the tableau. Routes show their cost `c`, goods `x` if they carry any
+/// or are basic, and reduced cost `d` if they are not basic. Sources and
+/// destinations are numbered from one, and their totals follow the goods
+fn tableau<T: Number>(snapshot: &Snapshot<T>) -> Vec<Vec<Entry>> {
+    let (m, n) = (snapshot.u.len(), snapshot.v.len());
+    let cells = snapshot
+        .cells
+        .iter()
+        .map(|x| ((x.source, x.destination), x))
+        .collect::<HashMap<_, _>>();
+    let (mut shipped, mut received) = (vec![T::zero(); m], vec![T::zero(); n]);
+    for cell in snapshot.cells.iter() {
+        shipped[cell.source] += cell.amount;
+        received[cell.destination] += cell.amount;
+    }
+    let mut rows = vec![];
+
+    let mut header = vec![Entry::new("")];
+    header.extend((1..=n).map(|j| Entry::new(format!("D{j}"))));
+    header.extend([Entry::new("Supply"), Entry::new("u")]);
+    rows.push(header);
+
+    for (i, total) in shipped.iter().enumerate() {
+        let mut row = vec![Entry::new(format!("S{}", i + 1))];
+        for j in 0..n {
+            // Routes, which aren't stored, are forbidden and empty
+            let (cost, amount, reduced) = match cells.get(&(i, j)) {
+                Some(cell) => (cell.cost, cell.amount, cell.reduced_cost),
+                None => {
+                    let cost = Cost::forbidden();
+                    (cost, T::zero(), Some(cost - snapshot.u[i] - snapshot.v[j]))
+                }
+            };
+            let mut text = format!("c={cost}");
+            if reduced.is_none() || amount.is_positive() {
+                text += &format!(" x={amount}");
+            }
+            if let Some(reduced) = reduced {
+                text += &format!(" d={reduced}");
+            }
+            row.push(Entry {
+                text,
+                basic: reduced.is_none(),
+            });
+        }
+        row.push(Entry::new(total));
+        row.push(Entry::new(snapshot.u[i]));
+        rows.push(row);
+    }
+
+    let mut demand = vec![Entry::new("Demand")];
+    demand.extend(received.iter().map(Entry::new));
+    demand.extend([
+        Entry::new(shipped.iter().copied().sum::<T>()),
+        Entry::new(""),
+    ]);
+    rows.push(demand);
+
+    let mut potentials = vec![Entry::new("v")];
+    potentials.extend(snapshot.v.iter().map(Entry::new));
+    potentials.extend([Entry::new(""), Entry::new("")]);
+    rows.push(potentials);
+    rows
+}
+
+/// Format of a rendered tableau
+pub trait Renderer {
+    fn render<T: Number>(snapshot: &Snapshot<T>) -> String;
+}
+
+/// Columns aligned with spaces, basic cells in square brackets
+pub struct PlainText;
+impl Renderer for PlainText {
+    fn render<T: Number>(snapshot: &Snapshot<T>) -> String {
+        let rows = tableau(snapshot)
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|entry| match entry.basic {
+                        true => format!("[{}]", entry.text),
+                        false => entry.text,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let widths = (0..rows[0].len())
+            .map(|col| rows.iter().map(|row| row[col].len()).max().unwrap_or(0))
+            .collect::<Vec<_>>();
+
+        let mut text = String::new();
+        for row in rows.iter() {
+            let line = row
+                .iter()
+                .zip(widths.iter())
+                .map(|(entry, width)| format!("{entry:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            text += line.trim_end();
+            text += "\n";
+        }
+        text + &format!("Objective: {}\n", snapshot.objective)
+    }
+}
+
+/// Table with basic cells in bold
+pub struct Markdown;
+impl Renderer for Markdown {
+    fn render<T: Number>(snapshot: &Snapshot<T>) -> String {
+        let rows = tableau(snapshot);
+        let mut text = String::new();
+        for (idx, row) in rows.iter().enumerate() {
+            let cells = row
+                .iter()
+                .map(|entry| match entry.basic {
+                    true => format!("**{}**", entry.text),
+                    false => entry.text.clone(),
+                })
+                .collect::<Vec<_>>();
+            text += &format!("| {} |\n", cells.join(" | "));
+            if idx == 0 {
+                text += &format!("|{}\n", "---|".repeat(row.len()));
+            }
+        }
+        text + &format!("\nObjective: {}\n", snapshot.objective)
+    }
+}
+
+/// `tabular` environment with basic cells in bold
+pub struct Latex;
+impl Renderer for Latex {
+    fn render<T: Number>(snapshot: &Snapshot<T>) -> String {
+        let rows = tableau(snapshot);
+        let n = rows[0].len() - 3;
+        let mut text = format!("\\begin{{tabular}}{{l|{}|c|c}}\n", "c".repeat(n));
+        for (idx, row) in rows.iter().enumerate() {
+            let cells = row
+                .iter()
+                .map(|entry| match entry.basic {
+                    true => format!("\\textbf{{{}}}", entry.text),
+                    false => entry.text.clone(),
+                })
+                .collect::<Vec<_>>();
+            text += &format!("{} \\\\\n", cells.join(" & "));
+            if idx == 0 || idx == rows.len() - 3 {
+                text += "\\hline\n";
+            }
+        }
+        text + "\\end{tabular}\n"
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use crate::number::Number;
+use crate::problem::Named;
+use crate::solver::SolverStats;
+
+/// Route with goods shipped along it
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Route<T = i32> {
+    pub source: usize,
+    pub destination: usize,
+    pub amount: T,
+}
+
+/// Solution of the original problem. Dummy nodes, which balance supply and
+/// demand, are not included, their shipments are in `unmet_demand` and
+/// `leftover_supply` of the stats
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Solution<T = i32> {
+    /// Goods shipped along every stored route, which are the lanes of the problem
+    /// and forbidden routes in the basis, ordered by source and destination.
+    /// Other routes carry nothing
+    pub shipments: Vec<Route<T>>,
+    pub objective: T,
+    /// Dual potentials of sources
+    pub u: Vec<T>,
+    /// Dual potentials of destinations
+    pub v: Vec<T>,
+    /// Reduced costs (source, destination, reduced cost) of non-basic lanes
+    pub reduced_costs: Vec<(usize, usize, T)>,
+    /// Basic routes (source, destination), including degenerate ones
+    pub basis: Vec<(usize, usize)>,
+    pub stats: SolverStats<T>,
+    /// Names of the problem's sources
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// Names of the problem's destinations
+    #[serde(default)]
+    pub destinations: Vec<String>,
+}
+
+impl<T> Named for Solution<T> {
+    fn sources(&self) -> &[String] {
+        &self.sources
+    }
+
+    fn destinations(&self) -> &[String] {
+        &self.destinations
+    }
+}
+
+impl<T: Number> Solution<T> {
+    /// Routes carrying goods
+    pub fn routes(&self) -> impl Iterator<Item = Route<T>> + '_ {
+        self.shipments
+            .iter()
+            .filter(|x| x.amount.is_positive())
+            .copied()
+    }
+
+    /// Goods shipped from the source to the destination
+    pub fn shipment(&self, source: usize, destination: usize) -> T {
+        self.shipments
+            .binary_search_by_key(&(source, destination), |x| (x.source, x.destination))
+            .map_or(T::zero(), |idx| self.shipments[idx].amount)
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::HashSet;
+
+use super::TransportationSolver;
+use crate::number::Number;
+use crate::solution::Solution;
+
+impl<T: Number> TransportationSolver<T> {
+    /// Non-basic allowed routes with zero reduced costs. Pivoting on them changes
+    /// the plan, but not its cost
+    fn ties(&self) -> Vec<(usize, usize)> {
+        self.routes
+            .iter()
+            .filter(|x| !x.base && !x.cost.is_forbidden() && self.reduced(x).is_zero())
+            .map(|x| (x.source, x.destination))
+            .collect()
+    }
+
+    /// Basic cells along with the non-basic ones at their capacities,
+    /// which identify the basic solution
+    fn state(&self) -> Vec<(usize, usize, bool)> {
+        let mut state = self
+            .routes
+            .iter()
+            .filter(|x| x.base || x.val.is_positive())
+            .map(|x| (x.source, x.destination, x.base))
+            .collect::<Vec<_>>();
+        state.sort();
+        state
+    }
+
+    /// Whether the optimum is not unique
+    pub fn has_alternative_optima(&self) -> bool {
+        self.alternative_optima(2).len() > 1
+    }
+
+    /// Distinct optimal plans, starting with the current one, at most `limit` of them.
+    /// Bases reachable by pivoting on zero reduced costs are searched, and degenerate
+    /// ones may repeat a plan, so at most `limit * (m + n)` bases are explored.
+    /// Empty before solving or if the problem is infeasible
+    pub fn alternative_optima(&self, limit: usize) -> Vec<Solution<T>> {
+        let Some(solution) = self.solution().filter(|x| x.stats.feasible) else {
+            return vec![];
+        };
+        let max_explored = limit * (self.m + self.n);
+        let mut visited = HashSet::from([self.state()]);
+        let mut plans = vec![self.shipments(self.m, self.n)];
+        let mut solutions = vec![solution];
+        let mut stack = vec![self.clone()];
+        let mut explored = 0;
+
+        while let Some(solver) = stack.pop() {
+            if solutions.len() >= limit || explored >= max_explored {
+                break;
+            }
+            explored += 1;
+
+            for entering in solver.ties() {
+                let mut next = solver.clone();
+                let chain = next.find_chain(entering);
+                next.apply_chain(&chain, false);
+                if !visited.insert(next.state()) {
+                    continue;
+                }
+                let (unmet_demand, leftover_supply) = (next.unmet_demand(), next.leftover_supply());
+                if let Some(stats) = next.stats.as_mut() {
+                    stats.unmet_demand = unmet_demand;
+                    stats.leftover_supply = leftover_supply;
+                }
+
+                // Pivots don't store other routes, so plans list the same ones
+                let flows = next.shipments(next.m, next.n);
+                let repeated = plans.iter().any(|plan| {
+                    plan.iter()
+                        .zip(flows.iter())
+                        .all(|(a, b)| a.amount.compare(&b.amount).is_eq())
+                });
+                if !repeated {
+                    solutions.push(next.solution().expect("Solver should be solved"));
+                    plans.push(flows);
+                    if solutions.len() >= limit {
+                        break;
+                    }
+                }
+                stack.push(next);
+            }
+        }
+        solutions.truncate(limit);
+        solutions
+    }
+}
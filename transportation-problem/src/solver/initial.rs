@@ -0,0 +1,291 @@
+use crate::cost::Cost;
+use crate::number::Number;
+use crate::sparse::SparseProblem;
+
+/// Costs of all routes, forbidden ones have big-M parts
+pub type Costs<'a, T> = dyn Fn(usize, usize) -> Cost<T> + 'a;
+
+/// Method of finding the initial basic feasible solution
+pub trait InitialSolution {
+    const NAME: &'static str;
+
+    /// Returns m + n - 1 basic cells with their allocations
+    /// for a balanced problem
+    fn allocate<T: Number>(problem: &SparseProblem<T>, costs: &Costs<T>) -> Vec<(usize, usize, T)>;
+}
+
+/// Row or column of the grid
+#[derive(Clone, Copy)]
+enum Line {
+    Row(usize),
+    Col(usize),
+}
+
+/// Rows and columns, which are not crossed out yet
+struct Remaining<T> {
+    supply: Vec<T>,
+    demand: Vec<T>,
+    rows: Vec<bool>,
+    cols: Vec<bool>,
+    /// Line crossed out by the last step, `None` before the first one
+    crossed: Option<Line>,
+}
+
+impl<T> Remaining<T> {
+    fn rows(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.rows.len()).filter(|i| self.rows[*i])
+    }
+
+    fn cols(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.cols.len()).filter(|j| self.cols[*j])
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.rows().flat_map(|i| self.cols().map(move |j| (i, j)))
+    }
+}
+
+/// Allocates to the picked cell and crosses out its row or column until
+/// everything is crossed out. Exactly one line is crossed out per step
+/// (except for the last one), so the basis always has m + n - 1 cells,
+/// even if some of them are degenerate
+fn cross_out<T, F>(problem: &SparseProblem<T>, mut pick: F) -> Vec<(usize, usize, T)>
+where
+    T: Number,
+    F: FnMut(&Remaining<T>) -> (usize, usize),
+{
+    let (m, n) = (problem.supply.len(), problem.demand.len());
+    let mut remaining = Remaining {
+        supply: problem.supply.clone(),
+        demand: problem.demand.clone(),
+        rows: vec![true; m],
+        cols: vec![true; n],
+        crossed: None,
+    };
+    let (mut rows_left, mut cols_left) = (m, n);
+    let mut base = Vec::with_capacity(m + n - 1);
+
+    loop {
+        // Pick the maximum allowed goods
+        let (i, j) = pick(&remaining);
+        let available = remaining.supply[i].lesser(remaining.demand[j]);
+
+        // Update
+        remaining.supply[i] -= available;
+        remaining.demand[j] -= available;
+        base.push((i, j, available));
+
+        // Cross out
+        if rows_left == 1 && cols_left == 1 {
+            break;
+        }
+        if remaining.supply[i].is_zero() && rows_left > 1 {
+            remaining.rows[i] = false;
+            remaining.crossed = Some(Line::Row(i));
+            rows_left -= 1;
+        } else {
+            remaining.cols[j] = false;
+            remaining.crossed = Some(Line::Col(j));
+            cols_left -= 1;
+        }
+    }
+
+    debug_assert!(remaining.supply.iter().copied().sum::<T>().is_zero());
+    debug_assert!(remaining.demand.iter().copied().sum::<T>().is_zero());
+    base
+}
+
+/// Fills the grid from the top left corner, ignoring costs
+pub struct NorthWest;
+impl InitialSolution for NorthWest {
+    const NAME: &'static str = "north-west";
+
+    fn allocate<T: Number>(
+        problem: &SparseProblem<T>,
+        _costs: &Costs<T>,
+    ) -> Vec<(usize, usize, T)> {
+        cross_out(problem, |remaining| {
+            let i = remaining.rows().next().expect("No rows left");
+            let j = remaining.cols().next().expect("No columns left");
+            (i, j)
+        })
+    }
+}
+
+/// Allocates to the cheapest remaining cell. Lanes are sorted once, and
+/// when none of them is left, the first remaining forbidden route is taken
+pub struct LeastCost;
+impl InitialSolution for LeastCost {
+    const NAME: &'static str = "least-cost";
+
+    fn allocate<T: Number>(problem: &SparseProblem<T>, costs: &Costs<T>) -> Vec<(usize, usize, T)> {
+        let mut lanes = problem
+            .lanes
+            .iter()
+            .map(|x| (x.source, x.destination))
+            .collect::<Vec<_>>();
+        lanes.sort_by_key(|(i, j)| (costs(*i, *j), *i, *j));
+        let mut lanes = lanes.into_iter().peekable();
+
+        cross_out(problem, |remaining| {
+            // Crossed out lines stay crossed out, so skipped lanes are never needed
+            while let Some((i, j)) = lanes.peek() {
+                match remaining.rows[*i] && remaining.cols[*j] {
+                    true => return (*i, *j),
+                    false => lanes.next(),
+                };
+            }
+            remaining.cells().next().expect("No cells left")
+        })
+    }
+}
+
+/// Two cheapest cells of a line by their positions along it, the first of
+/// equally cheap ones comes first
+type Cheapest<T> = ((usize, Cost<T>), Option<(usize, Cost<T>)>);
+
+/// Vogel's approximation method: allocates in the line with the largest
+/// difference between its two cheapest cells. These are kept for every line,
+/// and only lines, which lost one of them, are scanned again
+pub struct Vogel;
+impl Vogel {
+    fn cheapest<T: Number>(cells: impl Iterator<Item = (usize, Cost<T>)>) -> Cheapest<T> {
+        let (mut first, mut second) = (None, None);
+        for (idx, cost) in cells {
+            match first {
+                Some((_, min)) if cost >= min => {
+                    if second.is_none_or(|(_, x)| cost < x) {
+                        second = Some((idx, cost));
+                    }
+                }
+                _ => {
+                    second = first;
+                    first = Some((idx, cost));
+                }
+            }
+        }
+        (first.expect("No cells left"), second)
+    }
+
+    /// Difference between the two smallest costs, or the cost itself
+    /// if there is only one
+    fn penalty<T: Number>(cheapest: &Cheapest<T>) -> Cost<T> {
+        match cheapest {
+            ((_, first), Some((_, second))) => *second - *first,
+            ((_, first), None) => *first,
+        }
+    }
+}
+
+impl InitialSolution for Vogel {
+    const NAME: &'static str = "vogel";
+
+    fn allocate<T: Number>(problem: &SparseProblem<T>, costs: &Costs<T>) -> Vec<(usize, usize, T)> {
+        let touches = |cheapest: &Cheapest<T>, idx: usize| {
+            cheapest.0 .0 == idx || cheapest.1.is_some_and(|x| x.0 == idx)
+        };
+        let (mut rows, mut cols) = (vec![], vec![]);
+        cross_out(problem, |remaining| {
+            let row = |i| Self::cheapest(remaining.cols().map(|j| (j, costs(i, j))));
+            let col = |j| Self::cheapest(remaining.rows().map(|i| (i, costs(i, j))));
+            match remaining.crossed {
+                None => {
+                    rows = (0..remaining.rows.len()).map(row).collect();
+                    cols = (0..remaining.cols.len()).map(col).collect();
+                }
+                Some(Line::Row(k)) => {
+                    for j in remaining.cols() {
+                        if touches(&cols[j], k) {
+                            cols[j] = col(j);
+                        }
+                    }
+                }
+                Some(Line::Col(l)) => {
+                    for i in remaining.rows() {
+                        if touches(&rows[i], l) {
+                            rows[i] = row(i);
+                        }
+                    }
+                }
+            }
+
+            // Penalties of rows and columns, paired with the cheapest cell
+            let penalties = remaining
+                .rows()
+                .map(|i| (Self::penalty(&rows[i]), (i, rows[i].0 .0)))
+                .chain(
+                    remaining
+                        .cols()
+                        .map(|j| (Self::penalty(&cols[j]), (cols[j].0 .0, j))),
+                );
+
+            // Largest penalty, ties are broken by the cheapest cell
+            penalties
+                .min_by_key(|(penalty, (i, j))| (-*penalty, costs(*i, *j)))
+                .map(|(_, cell)| cell)
+                .expect("No lines left")
+        })
+    }
+}
+
+/// Russell's approximation method: allocates to the cell with the most
+/// negative cost reduced by the largest costs of its row and column.
+/// The largest costs and the best cell of every row are kept, and only
+/// those, which depend on the crossed out line, are found again
+pub struct Russell;
+impl InitialSolution for Russell {
+    const NAME: &'static str = "russell";
+
+    fn allocate<T: Number>(problem: &SparseProblem<T>, costs: &Costs<T>) -> Vec<(usize, usize, T)> {
+        let (mut u, mut v, mut best) = (vec![], vec![], vec![]);
+        cross_out(problem, |remaining| {
+            let row_max = |i| remaining.cols().map(|j| costs(i, j)).max();
+            let col_max = |j| remaining.rows().map(|i| costs(i, j)).max();
+            // Largest cost of the row doesn't change the best cell of it
+            let best_col = |i, v: &[Cost<T>]| {
+                remaining
+                    .cols()
+                    .min_by_key(|j| costs(i, *j) - v[*j])
+                    .expect("No columns left")
+            };
+            match remaining.crossed {
+                None => {
+                    u = (0..remaining.rows.len()).flat_map(row_max).collect();
+                    v = (0..remaining.cols.len()).flat_map(col_max).collect();
+                    best = (0..remaining.rows.len()).map(|i| best_col(i, &v)).collect();
+                }
+                Some(Line::Row(k)) => {
+                    let mut lowered = vec![false; v.len()];
+                    for j in remaining.cols() {
+                        if costs(k, j) == v[j] {
+                            v[j] = col_max(j).expect("No rows left");
+                            lowered[j] = true;
+                        }
+                    }
+                    // Other cells of the row only got more expensive
+                    for i in remaining.rows() {
+                        if lowered[best[i]] {
+                            best[i] = best_col(i, &v);
+                        }
+                    }
+                }
+                Some(Line::Col(l)) => {
+                    for i in remaining.rows() {
+                        if costs(i, l) == u[i] {
+                            u[i] = row_max(i).expect("No columns left");
+                        }
+                        if best[i] == l {
+                            best[i] = best_col(i, &v);
+                        }
+                    }
+                }
+            }
+
+            remaining
+                .rows()
+                .map(|i| (i, best[i]))
+                .min_by_key(|(i, j)| costs(*i, *j) - u[*i] - v[*j])
+                .expect("No cells left")
+        })
+    }
+}
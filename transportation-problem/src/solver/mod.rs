@@ -0,0 +1,621 @@
+mod alternative;
+pub mod initial;
+pub mod observer;
+pub mod pricing;
+mod routes;
+pub mod sensitivity;
+mod tree;
+pub mod warm;
+
+use std::cmp::Ordering;
+
+use crate::certificate::Certificate;
+use crate::cost::Cost;
+use crate::error::SolverError;
+use crate::number::Number;
+use crate::problem::{Balancing, Dummy, Penalties, Problem};
+use crate::solution::{Route, Solution};
+use crate::sparse::SparseProblem;
+
+use crate::utils::DisjointSets;
+use initial::{InitialSolution, NorthWest};
+use observer::{Observer, Pivot};
+use pricing::{Bland, Dantzig, Pricing};
+use routes::{Cell, Routes};
+use tree::SpanningTree;
+use warm::WarmStart;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransportationSolver<T = i32> {
+    /// Balanced problem
+    problem: SparseProblem<T>,
+    routes: Routes<T>,
+    base: Vec<(usize, usize)>,
+    tree: SpanningTree<T>,
+    pub stats: Option<SolverStats<T>>,
+    /// Number of pivots, after which solving is given up
+    pub max_iterations: Option<usize>,
+    /// Balancing of the original problem, used again if a change needs another dummy node
+    balancing: Balancing<T>,
+    dummy: Option<Dummy>,
+    m: usize,
+    n: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize, Default, Debug)]
+pub struct SolverStats<T = i32> {
+    pub objective: T,
+    /// Whether the demand is met without forbidden routes
+    pub feasible: bool,
+    /// Name of the initial solution method, or `artificial`,
+    /// if its allocation exceeds capacities
+    pub initial: String,
+    /// Name of the rule choosing entering cells
+    pub pricing: String,
+    pub initial_objective: T,
+    pub iterations: usize,
+    pub avg_chain_len: f32,
+    /// Number of sources without the dummy one
+    pub m: usize,
+    /// Number of destinations without the dummy one
+    pub n: usize,
+    /// Demand of each destination covered by the dummy source
+    pub unmet_demand: Vec<T>,
+    /// Supply of each source shipped to the dummy destination
+    pub leftover_supply: Vec<T>,
+    /// Set if the solution was re-optimized from the previous basis
+    pub warm_start: Option<WarmStart>,
+}
+
+impl<T> SolverStats<T> {
+    /// Iterations of the last solve from scratch, which the warm start didn't need.
+    /// Negative if it took more of them. That solve was of the problem before the
+    /// changes, so it is only an estimate, which doesn't cost another solve
+    pub fn saved_iterations(&self) -> Option<isize> {
+        self.warm_start
+            .as_ref()
+            .map(|x| x.cold_iterations as isize - self.iterations as isize)
+    }
+}
+
+impl<T: Number> TransportationSolver<T> {
+    pub fn new(problem: Problem<T>) -> Result<Self, SolverError<T>> {
+        Self::with_balancing(problem, Balancing::default())
+    }
+
+    /// Unbalanced problems are solved with a dummy node,
+    /// which charges the given penalties per unit of imbalance
+    pub fn with_penalties(
+        problem: Problem<T>,
+        penalties: Penalties<T>,
+    ) -> Result<Self, SolverError<T>> {
+        Self::with_balancing(problem, Balancing::Dummy(penalties))
+    }
+
+    pub fn with_balancing(
+        problem: Problem<T>,
+        balancing: Balancing<T>,
+    ) -> Result<Self, SolverError<T>> {
+        problem.validate_shape()?;
+        Self::sparse(problem.sparse(), balancing)
+    }
+
+    /// Solver of a problem, which lists only the lanes that can be used.
+    /// Memory and the work of every iteration grow with the number of lanes
+    pub fn sparse(
+        problem: SparseProblem<T>,
+        balancing: Balancing<T>,
+    ) -> Result<Self, SolverError<T>> {
+        problem.validate()?;
+        let (m, n) = (problem.supply.len(), problem.demand.len());
+        let penalties = match balancing.clone() {
+            Balancing::Strict
+                if problem
+                    .total_supply()
+                    .compare(&problem.total_demand())
+                    .is_ne() =>
+            {
+                return Err(SolverError::Unbalanced {
+                    supply: problem.total_supply(),
+                    demand: problem.total_demand(),
+                })
+            }
+            Balancing::Strict => Penalties::default(),
+            Balancing::Dummy(penalties) => penalties,
+        };
+        if penalties.shortage.len() > n {
+            return Err(SolverError::DimensionMismatch {
+                what: "shortage penalties".to_string(),
+                expected: n,
+                found: penalties.shortage.len(),
+            });
+        }
+        if penalties.surplus.len() > m {
+            return Err(SolverError::DimensionMismatch {
+                what: "surplus penalties".to_string(),
+                expected: m,
+                found: penalties.surplus.len(),
+            });
+        }
+
+        let (problem, dummy) = problem.balance(&penalties);
+        let (m, n) = (problem.supply.len(), problem.demand.len());
+        Ok(TransportationSolver {
+            routes: Routes::new(&problem),
+            problem,
+            base: vec![],
+            tree: SpanningTree::default(),
+            stats: None,
+            max_iterations: None,
+            balancing,
+            dummy,
+            m,
+            n,
+        })
+    }
+
+    /// Demand of each real destination covered by the dummy source
+    fn unmet_demand(&self) -> Vec<T> {
+        match self.dummy {
+            Some(Dummy::Source) => (0..self.n)
+                .map(|j| self.routes.get(self.m - 1, j).map_or(T::zero(), |x| x.val))
+                .collect(),
+            _ => vec![T::zero(); self.n - self.dummy.is_some() as usize],
+        }
+    }
+
+    /// Supply of each real source shipped to the dummy destination
+    fn leftover_supply(&self) -> Vec<T> {
+        match self.dummy {
+            Some(Dummy::Destination) => (0..self.m)
+                .map(|i| self.routes.get(i, self.n - 1).map_or(T::zero(), |x| x.val))
+                .collect(),
+            _ => vec![T::zero(); self.m - self.dummy.is_some() as usize],
+        }
+    }
+
+    fn reduced(&self, cell: &Cell<T>) -> Cost<T> {
+        cell.cost
+            - self.tree.potentials[cell.source]
+            - self.tree.potentials[self.m + cell.destination]
+    }
+
+    /// Non-basic cells sit at their lower bound, zero, or at their capacity.
+    /// Moving the cell away from its bound improves the objective if its reduced
+    /// cost is negative at the lower bound or positive at the upper one
+    fn is_improving(&self, cell: &Cell<T>) -> bool {
+        !cell.base
+            && match cell.val.is_positive() {
+                true => self.reduced(cell).is_positive(),
+                false => {
+                    self.reduced(cell).is_negative()
+                        && cell.capacity.is_none_or(|x| x.is_positive())
+                }
+            }
+    }
+
+    /// Returns whether the initial solution needs artificial routes,
+    /// since the allocation exceeds some capacity
+    fn initialize<I: InitialSolution>(&mut self) -> bool {
+        let allocation = I::allocate(&self.problem, &|i, j| self.routes.cost(i, j));
+        self.routes.clear();
+        if allocation.iter().any(|(i, j, val)| {
+            self.routes
+                .get(*i, *j)
+                .and_then(|x| x.capacity)
+                .is_some_and(|x| val.compare(&x).is_gt())
+        }) {
+            self.add_artificial();
+            return true;
+        }
+
+        self.base = Vec::with_capacity(self.m + self.n - 1);
+        for (i, j, val) in allocation {
+            let idx = self.routes.insert(i, j);
+            (self.routes[idx].val, self.routes[idx].base) = (val, true);
+            self.base.push((i, j));
+        }
+        self.complete_basis();
+        self.tree = SpanningTree::new(self.m, self.n, &self.base, &self.routes);
+        false
+    }
+
+    /// Starts from an artificial source and destination, which take all supply and
+    /// demand along forbidden routes, so that every real route is empty. Routes
+    /// between them are free and carry nothing, since the problem is balanced
+    fn add_artificial(&mut self) {
+        let (m, n) = (self.m, self.n);
+        self.routes.push_row();
+        self.base = Vec::with_capacity(m + n + 1);
+        let supply = self
+            .problem
+            .supply
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (i, n, *x));
+        let demand = self
+            .problem
+            .demand
+            .iter()
+            .enumerate()
+            .map(|(j, x)| (m, j, *x));
+        for (i, j, val) in supply.chain(demand).chain([(m, n, T::zero())]) {
+            let idx = self.routes.insert(i, j);
+            (self.routes[idx].val, self.routes[idx].base) = (val, true);
+            self.base.push((i, j));
+        }
+        self.routes[(m, n)].cost = Cost::default();
+        (self.m, self.n) = (m + 1, n + 1);
+        self.tree = SpanningTree::new(self.m, self.n, &self.base, &self.routes);
+    }
+
+    /// Removes the artificial source and destination. Routes between them are the
+    /// only ones carrying goods in a feasible plan, so the plan doesn't change
+    fn drop_artificial(&mut self) {
+        (self.m, self.n) = (self.m - 1, self.n - 1);
+        let (m, n) = (self.m, self.n);
+        self.routes.truncate(m, n);
+        self.base.retain(|(i, j)| *i < m && *j < n);
+        self.complete_basis();
+        self.tree = SpanningTree::new(self.m, self.n, &self.base, &self.routes);
+    }
+
+    /// A degenerate basis with fewer than m + n - 1 cells doesn't connect all rows
+    /// and columns, so potentials and chains can't be derived. It is completed to
+    /// a spanning tree with the cheapest zero-valued cells. Lanes may not connect
+    /// everything, then the rest is connected by forbidden routes
+    fn complete_basis(&mut self) {
+        // Rows are 0..m, columns are m..m+n
+        let mut components = DisjointSets::new(self.m + self.n);
+        for (i, j) in self.base.iter() {
+            let joined = components.union(*i, self.m + *j);
+            debug_assert!(joined, "Basis should not contain cycles");
+        }
+        if self.base.len() == self.m + self.n - 1 {
+            return;
+        }
+
+        let mut candidates = (0..self.routes.len())
+            .filter(|idx| !self.routes[*idx].base)
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|idx| self.routes[*idx].cost);
+
+        for idx in candidates {
+            // Cell keeps its bound, so the plan doesn't change
+            let cell = &mut self.routes[idx];
+            if components.union(cell.source, self.m + cell.destination) {
+                cell.base = true;
+                self.base.push((cell.source, cell.destination));
+                if self.base.len() == self.m + self.n - 1 {
+                    return;
+                }
+            }
+        }
+
+        // Every column joins the first row, then the rest of the rows
+        // join the first column
+        let joins = (0..self.n)
+            .map(|j| (0, j))
+            .chain((1..self.m).map(|i| (i, 0)));
+        for (i, j) in joins {
+            if components.union(i, self.m + j) {
+                let idx = self.routes.insert(i, j);
+                self.routes[idx].base = true;
+                self.base.push((i, j));
+            }
+        }
+    }
+
+    /// Index of the non-basic cell, which improves the objective, picked by
+    /// the pricing rule, or `None` at the optimum. The search may start from
+    /// the given cell
+    fn entering<P: Pricing>(&self, start: usize) -> Option<usize> {
+        let gain = |idx: usize| {
+            let cell = &self.routes[idx];
+            self.is_improving(cell)
+                .then(|| match cell.val.is_positive() {
+                    true => self.reduced(cell),
+                    false => -self.reduced(cell),
+                })
+        };
+        P::pick(self.routes.len(), start, gain)
+    }
+
+    /// Cycle of the entering cell with basic cells
+    fn find_chain(&self, (i, j): (usize, usize)) -> Vec<(usize, usize)> {
+        self.tree.cycle(i, j)
+    }
+
+    /// Returns the amount of goods moved along the chain and the leaving cell.
+    /// Goods are added to the entering cell, unless it is at its capacity,
+    /// then they are taken from it
+    fn apply_chain(&mut self, chain: &[(usize, usize)], bland: bool) -> (T, (usize, usize)) {
+        // First variable is not basic
+        let cells = chain
+            .iter()
+            .map(|(i, j)| self.routes.find(*i, *j).expect("Chain cells are stored"))
+            .collect::<Vec<_>>();
+        debug_assert!(!self.routes[cells[0]].base);
+        let increase = !self.routes[cells[0]].val.is_positive();
+
+        // All other variables are basic
+        debug_assert!(cells
+            .iter()
+            .skip(1)
+            .map(|idx| self.routes[*idx].base)
+            .fold(true, |acc, x| acc & x));
+
+        // Cells at odd positions are donors, when goods are added to the entering
+        // cell, and recipients otherwise
+        let gains = |idx: usize| idx.is_multiple_of(2) == increase;
+
+        // Find the variable that is leaving the base, it is the first to reach
+        // either bound. The entering one goes last, since it stays non-basic.
+        // Bland's rule breaks ties by the smallest cell, in the order of pricing
+        let (min_index, min_value) = (1..chain.len())
+            .chain([0])
+            .filter_map(|idx| {
+                let cell = &self.routes[cells[idx]];
+                match gains(idx) {
+                    true => cell.capacity.map(|x| (idx, x - cell.val)),
+                    false => Some((idx, cell.val)),
+                }
+            })
+            .min_by(|(a_idx, a), (b_idx, b)| {
+                a.compare(b).then_with(|| match bland {
+                    true => cells[*a_idx].cmp(&cells[*b_idx]),
+                    false => Ordering::Equal,
+                })
+            })
+            .expect("Chain should have donors");
+
+        for (idx, cell) in cells.iter().enumerate() {
+            match gains(idx) {
+                true => self.routes[*cell].val += min_value,
+                false => self.routes[*cell].val -= min_value,
+            }
+        }
+
+        // Leaving variable is exactly at its bound
+        let (i, j) = chain[min_index];
+        let leaving = &mut self.routes[cells[min_index]];
+        leaving.val = match gains(min_index) {
+            true => leaving.capacity.expect("Only bounded cells reach capacity"),
+            false => T::zero(),
+        };
+        if min_index == 0 {
+            // Entering variable moves to its other bound
+            return (min_value, (i, j));
+        }
+
+        // Swap the entering and leaving variables in the base
+        let base_pos = self
+            .base
+            .iter()
+            .position(|x| *x == chain[min_index])
+            .expect("Inconsistency in bases");
+        self.base[base_pos] = chain[0];
+        self.routes[cells[0]].base = true;
+        self.routes[cells[min_index]].base = false;
+        self.tree.pivot(chain[0], (i, j), &self.routes);
+        (min_value, (i, j))
+    }
+
+    /// Finite potentials of the current basis. Basic forbidden routes give the
+    /// potentials big-M parts, which are replaced with a value large enough to
+    /// keep reduced costs of allowed routes non-negative below their capacities
+    /// and non-positive at them
+    fn potentials(&self) -> (Vec<T>, Vec<T>) {
+        let (u, v) = self.tree.potentials.split_at(self.m);
+        let big = self
+            .routes
+            .iter()
+            .filter(|x| !x.base && !x.cost.is_forbidden())
+            .filter_map(|x| {
+                let reduced = x.cost - u[x.source] - v[x.destination];
+                match x.val.is_positive() {
+                    true => reduced.big.is_negative().then_some(reduced.val),
+                    false => reduced.big.is_positive().then_some(-reduced.val),
+                }
+            })
+            .fold(T::zero(), |acc, x| match acc.compare(&x) {
+                Ordering::Less => x,
+                _ => acc,
+            });
+        let collapse = |x: &Cost<T>| x.val + x.big * big;
+        (
+            u.iter().map(collapse).collect(),
+            v.iter().map(collapse).collect(),
+        )
+    }
+
+    /// Goods along stored routes within the first `m` sources and `n` destinations
+    fn shipments(&self, m: usize, n: usize) -> Vec<Route<T>> {
+        self.routes
+            .ordered()
+            .filter(|x| x.source < m && x.destination < n)
+            .map(|x| Route {
+                source: x.source,
+                destination: x.destination,
+                amount: x.val,
+            })
+            .collect()
+    }
+
+    /// Solution of the original problem, available after solving
+    pub fn solution(&self) -> Option<Solution<T>> {
+        let stats = self.stats.clone()?;
+        let (m, n) = (stats.m, stats.n);
+        let (mut u, mut v) = self.potentials();
+        u.truncate(m);
+        v.truncate(n);
+
+        let reduced_costs = self
+            .routes
+            .ordered()
+            .filter(|x| x.source < m && x.destination < n)
+            .filter(|x| !x.base && !x.cost.is_forbidden())
+            .map(|x| {
+                let (i, j) = (x.source, x.destination);
+                (i, j, x.cost.val - u[i] - v[j])
+            })
+            .collect();
+
+        Some(Solution {
+            shipments: self.shipments(m, n),
+            objective: stats.objective,
+            reduced_costs,
+            u,
+            v,
+            basis: self
+                .base
+                .iter()
+                .filter(|(i, j)| *i < m && *j < n)
+                .copied()
+                .collect(),
+            stats,
+            sources: self.problem.sources.clone(),
+            destinations: self.problem.destinations.clone(),
+        })
+    }
+
+    /// Verifies optimality of the current solution of the balanced problem
+    pub fn certificate(&self) -> Certificate<T> {
+        let (u, v) = self.potentials();
+        Certificate::check_routes(&self.problem, &self.shipments(self.m, self.n), &u, &v)
+    }
+
+    /// Total cost of the plan, or an error if it doesn't fit into the numeric type
+    fn objective(&self) -> Result<Cost<T>, SolverError<T>> {
+        self.routes
+            .iter()
+            .try_fold(Cost::default(), |acc, cell| {
+                acc.checked_add(cell.cost.checked_mul(cell.val)?)
+            })
+            .ok_or(SolverError::Overflow)
+    }
+
+    pub fn solve(&mut self) -> Result<(), SolverError<T>> {
+        self.solve_with::<NorthWest>()
+    }
+
+    /// Pivots until the basis is optimal
+    fn iterate<P: Pricing, O: Observer<T>>(
+        &mut self,
+        iterations: &mut usize,
+        chain_lengths: &mut usize,
+        observer: &mut O,
+    ) -> Result<(), SolverError<T>> {
+        let mut degenerate_streak = 0;
+        let mut start = 0;
+        loop {
+            *iterations += 1;
+
+            // Degenerate pivots don't improve the objective and may cycle,
+            // so after too many of them Bland's rule takes over
+            let bland = P::ANTI_CYCLING || degenerate_streak > self.m + self.n;
+            let entering = match bland {
+                true => self.entering::<Bland>(start),
+                false => self.entering::<P>(start),
+            };
+            let Some(idx) = entering else {
+                return Ok(());
+            };
+            // First iteration doesn't pivot
+            if self.max_iterations.is_some_and(|x| *iterations > x) {
+                return Err(SolverError::IterationLimit {
+                    iterations: *iterations - 1,
+                });
+            }
+            start = (idx + 1) % self.routes.len();
+            let entering = (self.routes[idx].source, self.routes[idx].destination);
+            let chain = self.find_chain(entering);
+            *chain_lengths += chain.len();
+            let (amount, leaving) = self.apply_chain(&chain, bland);
+            match amount.is_zero() {
+                true => degenerate_streak += 1,
+                false => degenerate_streak = 0,
+            }
+            let pivot = Pivot {
+                iteration: *iterations,
+                entering,
+                chain,
+                leaving,
+                amount,
+            };
+            observer.pivot(self, &pivot);
+        }
+    }
+
+    pub fn solve_with<I: InitialSolution>(&mut self) -> Result<(), SolverError<T>> {
+        self.solve_using::<I, Dantzig>()
+    }
+
+    /// Solves starting with the initial solution `I`,
+    /// while the pricing rule `P` chooses entering cells
+    pub fn solve_using<I: InitialSolution, P: Pricing>(&mut self) -> Result<(), SolverError<T>> {
+        self.solve_observed::<I, P, _>(&mut ())
+    }
+
+    /// Same as `solve_using`, reporting the initial solution and every pivot
+    pub fn solve_observed<I, P, O>(&mut self, observer: &mut O) -> Result<(), SolverError<T>>
+    where
+        I: InitialSolution,
+        P: Pricing,
+        O: Observer<T>,
+    {
+        let mut iterations = 0;
+        let mut chain_lengths = 0;
+        let artificial = self.initialize::<I>();
+        observer.initial(self);
+        let initial_objective = self.objective()?.val;
+        self.iterate::<P, O>(&mut iterations, &mut chain_lengths, observer)?;
+
+        // Optimal solution uses forbidden or artificial routes only
+        // if there is no other way
+        let feasible = !self.objective()?.is_forbidden();
+        if artificial {
+            self.drop_artificial();
+            // Plan stays optimal, but the completed basis may be not
+            if feasible {
+                observer.initial(self);
+                self.iterate::<P, O>(&mut iterations, &mut chain_lengths, observer)?;
+            }
+        }
+
+        let initial = match artificial {
+            true => "artificial".to_string(),
+            false => I::NAME.to_string(),
+        };
+        self.finish(SolverStats {
+            feasible,
+            initial,
+            pricing: P::NAME.to_string(),
+            initial_objective,
+            iterations,
+            avg_chain_len: chain_lengths as f32 / iterations as f32,
+            ..Default::default()
+        })
+    }
+
+    /// Records the stats, filling in the objective and the dummy shipments
+    fn finish(&mut self, stats: SolverStats<T>) -> Result<(), SolverError<T>> {
+        let (unmet_demand, leftover_supply) = (self.unmet_demand(), self.leftover_supply());
+        let feasible = stats.feasible;
+        self.stats = Some(SolverStats {
+            objective: self.objective()?.val,
+            m: leftover_supply.len(),
+            n: unmet_demand.len(),
+            unmet_demand,
+            leftover_supply,
+            ..stats
+        });
+
+        match feasible {
+            true => Ok(()),
+            false => Err(SolverError::Infeasible),
+        }
+    }
+}
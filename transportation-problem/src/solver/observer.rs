@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+use super::TransportationSolver;
+use crate::cost::Cost;
+use crate::number::Number;
+
+/// Stored route of the tableau
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TableauCell<T = i32> {
+    pub source: usize,
+    pub destination: usize,
+    /// Forbidden routes have big-M parts
+    pub cost: Cost<T>,
+    /// Goods shipped along the route
+    pub amount: T,
+    /// `None` for basic routes
+    pub reduced_cost: Option<Cost<T>>,
+}
+
+/// Tableau of the balanced problem, including the dummy node and,
+/// during the first phase, the artificial source and destination.
+/// Routes, which aren't stored, are forbidden, non-basic and carry nothing
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot<T = i32> {
+    /// Stored routes ordered by source and destination
+    pub cells: Vec<TableauCell<T>>,
+    /// Basic routes (source, destination)
+    pub basis: Vec<(usize, usize)>,
+    /// Potentials of sources, big-M parts come from forbidden routes
+    pub u: Vec<Cost<T>>,
+    /// Potentials of destinations
+    pub v: Vec<Cost<T>>,
+    pub objective: Cost<T>,
+}
+
+/// Pivot of the simplex method
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Pivot<T = i32> {
+    pub iteration: usize,
+    pub entering: (usize, usize),
+    /// Cycle of the entering cell with basic cells, starting with the entering one
+    pub chain: Vec<(usize, usize)>,
+    /// Cell, which left the basis, or the entering one, if it only moved
+    /// to its other bound
+    pub leaving: (usize, usize),
+    /// Goods moved along the chain
+    pub amount: T,
+}
+
+/// Callbacks of the solver. The solver is passed, so that snapshots
+/// are only taken, when they are needed
+pub trait Observer<T: Number> {
+    /// Called after the initial solution, and again after the artificial
+    /// routes are dropped
+    fn initial(&mut self, _solver: &TransportationSolver<T>) {}
+
+    /// Called after every pivot
+    fn pivot(&mut self, _solver: &TransportationSolver<T>, _pivot: &Pivot<T>) {}
+}
+
+/// Observer, which ignores everything
+impl<T: Number> Observer<T> for () {}
+
+/// Tableau along with the pivot, which led to it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Step<T = i32> {
+    /// `None` for initial solutions
+    pub pivot: Option<Pivot<T>>,
+    pub snapshot: Snapshot<T>,
+}
+
+/// Observer collecting the whole history of solving
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Recorder<T = i32> {
+    pub steps: Vec<Step<T>>,
+}
+
+impl<T: Number> Observer<T> for Recorder<T> {
+    fn initial(&mut self, solver: &TransportationSolver<T>) {
+        self.steps.push(Step {
+            pivot: None,
+            snapshot: solver.snapshot().expect("Solver has a basis"),
+        });
+    }
+
+    fn pivot(&mut self, solver: &TransportationSolver<T>, pivot: &Pivot<T>) {
+        self.steps.push(Step {
+            pivot: Some(pivot.clone()),
+            snapshot: solver.snapshot().expect("Solver has a basis"),
+        });
+    }
+}
+
+impl<T: Number> TransportationSolver<T> {
+    /// Current tableau, available once there is an initial solution,
+    /// whose objective fits into the numeric type
+    pub fn snapshot(&self) -> Option<Snapshot<T>> {
+        if self.base.is_empty() {
+            return None;
+        }
+        let (u, v) = self.tree.potentials.split_at(self.m);
+        let cells = self
+            .routes
+            .ordered()
+            .map(|x| TableauCell {
+                source: x.source,
+                destination: x.destination,
+                cost: x.cost,
+                amount: x.val,
+                reduced_cost: (!x.base).then(|| x.cost - u[x.source] - v[x.destination]),
+            })
+            .collect();
+        Some(Snapshot {
+            cells,
+            basis: self.base.clone(),
+            u: u.to_vec(),
+            v: v.to_vec(),
+            objective: self.objective().ok()?,
+        })
+    }
+}
@@ -0,0 +1,100 @@
+use std::cmp::Reverse;
+
+use crate::cost::Cost;
+use crate::number::Number;
+
+/// Rule choosing the cell, which enters the basis
+pub trait Pricing {
+    const NAME: &'static str;
+    /// Whether the rule never cycles, when leaving cells are chosen by
+    /// the smallest index, so no other rule has to take over
+    const ANTI_CYCLING: bool = false;
+
+    /// Picks one of `count` stored routes in their order, for which `gain` is some,
+    /// or `None` at the optimum. The gain is the decrease of the objective per
+    /// unit moved, and `start` is the cell following the previously picked one
+    fn pick<T, F>(count: usize, start: usize, gain: F) -> Option<usize>
+    where
+        T: Number,
+        F: Fn(usize) -> Option<Cost<T>>;
+}
+
+/// Cells starting from `start` and wrapping around
+fn cyclic(count: usize, start: usize) -> impl Iterator<Item = usize> {
+    (start..count).chain(0..start.min(count))
+}
+
+/// Picks the largest gain over the whole grid, which usually
+/// takes the fewest pivots, but each one prices every cell
+pub struct Dantzig;
+impl Pricing for Dantzig {
+    const NAME: &'static str = "dantzig";
+
+    fn pick<T, F>(count: usize, _start: usize, gain: F) -> Option<usize>
+    where
+        T: Number,
+        F: Fn(usize) -> Option<Cost<T>>,
+    {
+        (0..count)
+            .filter_map(|idx| gain(idx).map(|x| (idx, x)))
+            .min_by_key(|(_, x)| Reverse(*x))
+            .map(|(idx, _)| idx)
+    }
+}
+
+/// Picks the first improving cell after the previous one
+pub struct FirstImproving;
+impl Pricing for FirstImproving {
+    const NAME: &'static str = "first-improving";
+
+    fn pick<T, F>(count: usize, start: usize, gain: F) -> Option<usize>
+    where
+        T: Number,
+        F: Fn(usize) -> Option<Cost<T>>,
+    {
+        cyclic(count, start).find(|idx| gain(*idx).is_some())
+    }
+}
+
+/// Splits the grid into blocks of about the square root of its size and picks
+/// the largest gain in the first block after the previous cell, which has any
+pub struct Partial;
+impl Pricing for Partial {
+    const NAME: &'static str = "partial";
+
+    fn pick<T, F>(count: usize, start: usize, gain: F) -> Option<usize>
+    where
+        T: Number,
+        F: Fn(usize) -> Option<Cost<T>>,
+    {
+        let block = count.isqrt().max(1);
+        let mut cells = cyclic(count, start).peekable();
+        while cells.peek().is_some() {
+            let best = cells
+                .by_ref()
+                .take(block)
+                .filter_map(|idx| gain(idx).map(|x| (idx, x)))
+                .min_by_key(|(_, x)| Reverse(*x));
+            if let Some((idx, _)) = best {
+                return Some(idx);
+            }
+        }
+        None
+    }
+}
+
+/// Picks the first improving cell of the grid. Along with the leaving cell
+/// chosen by the smallest index, it never cycles
+pub struct Bland;
+impl Pricing for Bland {
+    const NAME: &'static str = "bland";
+    const ANTI_CYCLING: bool = true;
+
+    fn pick<T, F>(count: usize, _start: usize, gain: F) -> Option<usize>
+    where
+        T: Number,
+        F: Fn(usize) -> Option<Cost<T>>,
+    {
+        (0..count).find(|idx| gain(*idx).is_some())
+    }
+}
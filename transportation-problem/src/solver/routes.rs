@@ -0,0 +1,160 @@
+use std::ops::{Index, IndexMut};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cost::Cost;
+use crate::number::Number;
+use crate::sparse::SparseProblem;
+
+/// Route of the balanced problem along with its state in the simplex method
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct Cell<T> {
+    pub(super) source: usize,
+    pub(super) destination: usize,
+    /// Forbidden routes have big-M parts
+    pub(super) cost: Cost<T>,
+    /// `None` if the route is unbounded
+    pub(super) capacity: Option<T>,
+    pub(super) val: T,
+    pub(super) base: bool,
+}
+
+/// Routes, which the solver works with. Lanes are stored from the start, while
+/// forbidden routes are only stored once the basis needs them, so memory and
+/// pricing scale with the number of lanes rather than with the whole grid
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(super) struct Routes<T> {
+    cells: Vec<Cell<T>>,
+    /// Cells of every row, ordered by destination
+    rows: Vec<Vec<usize>>,
+}
+
+impl<T: Number> Routes<T> {
+    /// Lanes are stored in row-major order, in which they are priced
+    pub(super) fn new(problem: &SparseProblem<T>) -> Self {
+        let mut cells = problem
+            .lanes
+            .iter()
+            .map(|lane| Cell {
+                source: lane.source,
+                destination: lane.destination,
+                cost: Cost::new(lane.cost),
+                capacity: lane.capacity,
+                val: T::zero(),
+                base: false,
+            })
+            .collect::<Vec<_>>();
+        cells.sort_by_key(|x| (x.source, x.destination));
+        let mut routes = Routes {
+            cells,
+            rows: vec![],
+        };
+        routes.truncate(problem.supply.len(), problem.demand.len());
+        routes
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub(super) fn iter(&self) -> impl Iterator<Item = &Cell<T>> {
+        self.cells.iter()
+    }
+
+    /// Stored routes ordered by source and destination
+    pub(super) fn ordered(&self) -> impl Iterator<Item = &Cell<T>> {
+        self.rows.iter().flatten().map(|idx| &self.cells[*idx])
+    }
+
+    /// Index of the stored route
+    pub(super) fn find(&self, i: usize, j: usize) -> Option<usize> {
+        let row = &self.rows[i];
+        row.binary_search_by_key(&j, |idx| self.cells[*idx].destination)
+            .ok()
+            .map(|pos| row[pos])
+    }
+
+    pub(super) fn get(&self, i: usize, j: usize) -> Option<&Cell<T>> {
+        self.find(i, j).map(|idx| &self.cells[idx])
+    }
+
+    /// Cost of the route, which is forbidden unless it is stored
+    pub(super) fn cost(&self, i: usize, j: usize) -> Cost<T> {
+        self.get(i, j).map_or(Cost::forbidden(), |x| x.cost)
+    }
+
+    /// Index of the route, which is stored as a forbidden one if it wasn't yet
+    pub(super) fn insert(&mut self, i: usize, j: usize) -> usize {
+        let row = &self.rows[i];
+        match row.binary_search_by_key(&j, |idx| self.cells[*idx].destination) {
+            Ok(pos) => row[pos],
+            Err(pos) => {
+                let idx = self.cells.len();
+                self.cells.push(Cell {
+                    source: i,
+                    destination: j,
+                    cost: Cost::forbidden(),
+                    capacity: None,
+                    val: T::zero(),
+                    base: false,
+                });
+                self.rows[i].insert(pos, idx);
+                idx
+            }
+        }
+    }
+
+    /// Adds a row without stored routes
+    pub(super) fn push_row(&mut self) {
+        self.rows.push(vec![]);
+    }
+
+    /// Drops rows and columns beyond the given numbers
+    pub(super) fn truncate(&mut self, m: usize, n: usize) {
+        self.cells.retain(|x| x.source < m && x.destination < n);
+        self.rows = vec![vec![]; m];
+        for (idx, cell) in self.cells.iter().enumerate() {
+            self.rows[cell.source].push(idx);
+        }
+        for row in self.rows.iter_mut() {
+            row.sort_by_key(|idx| self.cells[*idx].destination);
+        }
+    }
+
+    /// Empties all routes and makes them non-basic
+    pub(super) fn clear(&mut self) {
+        for cell in self.cells.iter_mut() {
+            cell.val = T::zero();
+            cell.base = false;
+        }
+    }
+}
+
+impl<T> Index<usize> for Routes<T> {
+    type Output = Cell<T>;
+
+    fn index(&self, idx: usize) -> &Cell<T> {
+        &self.cells[idx]
+    }
+}
+
+impl<T> IndexMut<usize> for Routes<T> {
+    fn index_mut(&mut self, idx: usize) -> &mut Cell<T> {
+        &mut self.cells[idx]
+    }
+}
+
+impl<T: Number> Index<(usize, usize)> for Routes<T> {
+    type Output = Cell<T>;
+
+    fn index(&self, (i, j): (usize, usize)) -> &Cell<T> {
+        self.get(i, j).expect("Route is not stored")
+    }
+}
+
+impl<T: Number> IndexMut<(usize, usize)> for Routes<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Cell<T> {
+        let idx = self.find(i, j).expect("Route is not stored");
+        &mut self.cells[idx]
+    }
+}
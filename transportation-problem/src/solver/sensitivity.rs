@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+use super::routes::Cell;
+use super::TransportationSolver;
+use crate::number::Number;
+use crate::problem::Dummy;
+
+/// Values of a parameter, for which the final basis stays optimal.
+/// Missing bounds are unbounded
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Interval<T = i32> {
+    pub value: T,
+    pub lower: Option<T>,
+    pub upper: Option<T>,
+}
+
+impl<T: Number> Interval<T> {
+    pub fn contains(&self, value: T) -> bool {
+        self.lower.is_none_or(|x| !value.compare(&x).is_lt())
+            && self.upper.is_none_or(|x| !value.compare(&x).is_gt())
+    }
+}
+
+/// Sensitivity of the optimal basis to changes of a single parameter.
+/// Ranges are those of the final basis, so with a degenerate optimum the plan
+/// may stay optimal slightly beyond them
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Sensitivity<T = i32> {
+    /// Costs of routes, for which the basis stays optimal, `None` for forbidden ones
+    pub costs: Vec<Vec<Option<Interval<T>>>>,
+    /// Supplies, for which the basis stays feasible. Difference is absorbed by
+    /// the dummy node, and a balanced problem is ranged as if it had one without
+    /// penalties, which takes a surplus or covers a shortage
+    pub supply: Vec<Interval<T>>,
+    /// Demands, for which the basis stays feasible, same as for supplies
+    pub demand: Vec<Interval<T>>,
+}
+
+/// Minimum of optional values, `None` is infinity
+fn lesser<T: Number>(acc: Option<T>, value: T) -> Option<T> {
+    Some(acc.map_or(value, |x| x.lesser(value)))
+}
+
+impl<T: Number> TransportationSolver<T> {
+    /// Range of a supply or demand, when goods are sent along tree paths, the first
+    /// one for a lower value and the second one for a higher value. Flows must stay
+    /// within their bounds, and forbidden routes unused
+    fn rhs_range(
+        &self,
+        value: T,
+        lowering: &[((usize, usize), bool)],
+        raising: &[((usize, usize), bool)],
+    ) -> Interval<T> {
+        // Lower value sends goods backwards, which takes them from the cells
+        // passed from row to column, and adds them to the others
+        let limit = |path: &[((usize, usize), bool)], backwards: bool| {
+            let mut limit = None;
+            for ((i, j), forward) in path.iter().copied() {
+                let cell = &self.routes[(i, j)];
+                if forward == backwards || cell.cost.is_forbidden() {
+                    limit = lesser(limit, cell.val);
+                }
+                if let Some(capacity) = cell.capacity.filter(|_| forward != backwards) {
+                    limit = lesser(limit, capacity - cell.val);
+                }
+            }
+            limit
+        };
+        Interval {
+            value,
+            lower: limit(lowering, true).map(|x| value - x),
+            upper: limit(raising, false).map(|x| value + x),
+        }
+    }
+
+    /// Ranging of costs, supplies and demands of the original problem,
+    /// available after solving a feasible one
+    pub fn sensitivity(&self) -> Option<Sensitivity<T>> {
+        let stats = self.stats.as_ref().filter(|x| x.feasible)?;
+        let (m, n) = (stats.m, stats.n);
+        let (u, v) = self.potentials();
+        // Routes without capacity stay unused at any cost
+        let fixed = |cell: &Cell<T>| cell.capacity.is_some_and(|x| x.is_zero());
+        let reduced = |cell: &Cell<T>| match cell.base || cell.cost.is_forbidden() || fixed(cell) {
+            true => None,
+            false => Some(cell.cost.val - u[cell.source] - v[cell.destination]),
+        };
+
+        let costs = (0..m)
+            .map(|i| {
+                (0..n)
+                    .map(|j| {
+                        let cell = self.routes.get(i, j)?;
+                        let value = cell.cost.val;
+                        if cell.cost.is_forbidden() {
+                            return None;
+                        }
+                        if !cell.base && fixed(cell) {
+                            return Some(Interval {
+                                value,
+                                lower: None,
+                                upper: None,
+                            });
+                        }
+                        if !cell.base {
+                            // Route stays unused while its reduced cost is non-negative,
+                            // or used up to its capacity while it is non-positive
+                            let reduced = reduced(cell).expect("Route is non-basic");
+                            return Some(match cell.val.is_positive() {
+                                true => Interval {
+                                    value,
+                                    lower: None,
+                                    upper: Some(value - reduced),
+                                },
+                                false => Interval {
+                                    value,
+                                    lower: Some(value - reduced),
+                                    upper: None,
+                                },
+                            });
+                        }
+
+                        // Changing the cost shifts potentials of the part of the tree
+                        // hanging on column j, so only reduced costs of cells between
+                        // the two parts change, by the same amount. Cells at their
+                        // capacities have non-positive reduced costs, so they limit
+                        // the opposite direction
+                        let child = self.tree.child((i, j));
+                        let mut side = vec![child != i; self.m + self.n];
+                        for node in self.tree.subtree(child) {
+                            side[node] = child == i;
+                        }
+                        let (mut decrease, mut increase) = (None, None);
+                        for other in self.routes.iter() {
+                            let Some(reduced) = reduced(other) else {
+                                continue;
+                            };
+                            let (k, l) = (other.source, other.destination);
+                            match (side[k], side[self.m + l], other.val.is_positive()) {
+                                (true, false, false) => increase = lesser(increase, reduced),
+                                (false, true, false) => decrease = lesser(decrease, reduced),
+                                (true, false, true) => decrease = lesser(decrease, -reduced),
+                                (false, true, true) => increase = lesser(increase, -reduced),
+                                _ => {}
+                            }
+                        }
+                        Some(Interval {
+                            value,
+                            lower: decrease.map(|x| value - x),
+                            upper: increase.map(|x| value + x),
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Change of a supply or demand is sent between its node and the dummy one.
+        // Without it, a free dummy column would hang on the row with the largest
+        // potential and a free dummy row on the column with the largest one,
+        // so that their reduced costs stay non-negative. A surplus is sent
+        // through the former and a shortage is covered through the latter
+        let (dummy_row, dummy_col) = (self.m - 1, self.m + self.n - 1);
+        let largest = |x: &[T]| {
+            (0..x.len())
+                .max_by(|a, b| x[*a].compare(&x[*b]))
+                .expect("Problem is not empty")
+        };
+        let (surplus, shortage) = (largest(&u), self.m + largest(&v));
+        let dummy = self.dummy.map(|x| match x {
+            Dummy::Source => dummy_row,
+            Dummy::Destination => dummy_col,
+        });
+        let supply = (0..m)
+            .map(|i| {
+                let (lowering, raising) = dummy.map_or((shortage, surplus), |x| (x, x));
+                let (lowering, raising) = (self.tree.path(i, lowering), self.tree.path(i, raising));
+                self.rhs_range(self.problem.supply[i], &lowering, &raising)
+            })
+            .collect();
+        let demand = (0..n)
+            .map(|j| {
+                let (lowering, raising) = dummy.map_or((surplus, shortage), |x| (x, x));
+                let (lowering, raising) = (
+                    self.tree.path(lowering, self.m + j),
+                    self.tree.path(raising, self.m + j),
+                );
+                self.rhs_range(self.problem.demand[j], &lowering, &raising)
+            })
+            .collect();
+
+        Some(Sensitivity {
+            costs,
+            supply,
+            demand,
+        })
+    }
+}
@@ -0,0 +1,379 @@
+use serde::{Deserialize, Serialize};
+
+use super::routes::Routes;
+use crate::cost::Cost;
+use crate::number::Number;
+
+/// Basis as a spanning tree rooted at the first row, as in the network simplex.
+/// Rows are nodes 0..m and columns are nodes m..m+n
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(super) struct SpanningTree<T> {
+    m: usize,
+    parent: Vec<Option<usize>>,
+    depth: Vec<usize>,
+    /// Next node in the preorder traversal, the last one points to the root,
+    /// so every subtree is a contiguous part of the thread
+    thread: Vec<usize>,
+    /// Previous node in the preorder traversal
+    rev_thread: Vec<usize>,
+    /// Neighbours of every node in the tree
+    adjacent: Vec<Vec<usize>>,
+    /// Potentials u of rows followed by v of columns,
+    /// u + v equals the cost of every basic cell
+    pub(super) potentials: Vec<Cost<T>>,
+}
+
+impl<T: Number> SpanningTree<T> {
+    pub(super) fn new(m: usize, n: usize, base: &[(usize, usize)], routes: &Routes<T>) -> Self {
+        let mut adjacent = vec![vec![]; m + n];
+        for (i, j) in base.iter().copied() {
+            adjacent[i].push(m + j);
+            adjacent[m + j].push(i);
+        }
+        let mut tree = SpanningTree {
+            m,
+            parent: vec![None; m + n],
+            depth: vec![0; m + n],
+            thread: vec![0; m + n],
+            rev_thread: vec![0; m + n],
+            adjacent,
+            potentials: vec![Cost::default(); m + n],
+        };
+
+        let order = tree.hang(0, None, routes);
+        debug_assert_eq!(order.len(), m + n, "Basis should be a spanning tree");
+        for (idx, node) in order.iter().enumerate() {
+            let next = order[(idx + 1) % order.len()];
+            tree.thread[*node] = next;
+            tree.rev_thread[next] = *node;
+        }
+        tree
+    }
+
+    /// Cell connecting two adjacent nodes
+    fn cell(&self, a: usize, b: usize) -> (usize, usize) {
+        match a < self.m {
+            true => (a, b - self.m),
+            false => (b, a - self.m),
+        }
+    }
+
+    /// Hangs the subtree of the root on the parent, updating depths and potentials.
+    /// Returns nodes of the subtree in preorder
+    fn hang(&mut self, root: usize, parent: Option<usize>, routes: &Routes<T>) -> Vec<usize> {
+        self.attach(root, parent, routes);
+        let mut order = vec![];
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            for idx in (0..self.adjacent[node].len()).rev() {
+                let next = self.adjacent[node][idx];
+                if Some(next) != self.parent[node] {
+                    self.attach(next, Some(node), routes);
+                    stack.push(next);
+                }
+            }
+        }
+        order
+    }
+
+    fn attach(&mut self, node: usize, parent: Option<usize>, routes: &Routes<T>) {
+        self.parent[node] = parent;
+        (self.depth[node], self.potentials[node]) = match parent {
+            Some(parent) => {
+                let (i, j) = self.cell(node, parent);
+                (
+                    self.depth[parent] + 1,
+                    routes.cost(i, j) - self.potentials[parent],
+                )
+            }
+            None => (0, Cost::default()),
+        };
+    }
+
+    /// Nodes of the subtree in preorder
+    pub(super) fn subtree(&self, root: usize) -> Vec<usize> {
+        let mut nodes = vec![root];
+        let mut node = self.thread[root];
+        while node != root && self.depth[node] > self.depth[root] {
+            nodes.push(node);
+            node = self.thread[node];
+        }
+        nodes
+    }
+
+    /// Cell connecting the node to its parent, `None` for the root
+    pub(super) fn parent_cell(&self, node: usize) -> Option<(usize, usize)> {
+        self.parent[node].map(|parent| self.cell(node, parent))
+    }
+
+    /// Node of the basic cell, which is farther from the root
+    pub(super) fn child(&self, (i, j): (usize, usize)) -> usize {
+        match self.parent[i] == Some(self.m + j) {
+            true => i,
+            false => self.m + j,
+        }
+    }
+
+    fn is_descendant(&self, mut node: usize, root: usize) -> bool {
+        while self.depth[node] > self.depth[root] {
+            node = self.parent[node].expect("Only the root has no parent");
+        }
+        node == root
+    }
+
+    /// Cells on the tree path between two nodes, paired with whether the path
+    /// passes them from row to column
+    pub(super) fn path(&self, mut from: usize, mut to: usize) -> Vec<((usize, usize), bool)> {
+        let mut path = vec![];
+        while from != to {
+            if self.depth[from] >= self.depth[to] {
+                let parent = self.parent[from].expect("Only the root has no parent");
+                path.push((self.cell(from, parent), from < self.m));
+                from = parent;
+            } else {
+                let parent = self.parent[to].expect("Only the root has no parent");
+                path.push((self.cell(to, parent), parent < self.m));
+                to = parent;
+            }
+        }
+        path
+    }
+
+    /// Cycle of the non-basic cell with the tree path between its row and column.
+    /// It starts with the cell and goes along its column first
+    pub(super) fn cycle(&self, i: usize, j: usize) -> Vec<(usize, usize)> {
+        let (mut row, mut col) = (i, self.m + j);
+        let (mut from_row, mut from_col) = (vec![], vec![]);
+        while row != col {
+            if self.depth[row] >= self.depth[col] {
+                let parent = self.parent[row].expect("Only the root has no parent");
+                from_row.push(self.cell(row, parent));
+                row = parent;
+            } else {
+                let parent = self.parent[col].expect("Only the root has no parent");
+                from_col.push(self.cell(col, parent));
+                col = parent;
+            }
+        }
+
+        let mut cycle = Vec::with_capacity(from_row.len() + from_col.len() + 1);
+        cycle.push((i, j));
+        cycle.extend(from_col);
+        cycle.extend(from_row.into_iter().rev());
+        cycle
+    }
+
+    /// Replaces the leaving cell with the entering one. The subtree cut off by
+    /// the leaving cell is hung on the entering one, so only its depths, thread
+    /// and potentials change
+    pub(super) fn pivot(
+        &mut self,
+        entering: (usize, usize),
+        leaving: (usize, usize),
+        routes: &Routes<T>,
+    ) {
+        let root = self.child(leaving);
+        let (i, j) = (entering.0, self.m + entering.1);
+        let (inner, outer) = match self.is_descendant(i, root) {
+            true => (i, j),
+            false => (j, i),
+        };
+
+        // Cut the subtree out of the thread
+        let last = *self.subtree(root).last().expect("Subtree has a root");
+        let (before, after) = (self.rev_thread[root], self.thread[last]);
+        self.thread[before] = after;
+        self.rev_thread[after] = before;
+
+        let (a, b) = (leaving.0, self.m + leaving.1);
+        self.adjacent[a].retain(|x| *x != b);
+        self.adjacent[b].retain(|x| *x != a);
+        self.adjacent[i].push(j);
+        self.adjacent[j].push(i);
+
+        // Hang it back right after the new parent
+        let order = self.hang(inner, Some(outer), routes);
+        let after = self.thread[outer];
+        let mut prev = outer;
+        for node in order.into_iter().chain([after]) {
+            self.thread[prev] = node;
+            self.rev_thread[node] = prev;
+            prev = node;
+        }
+        debug_assert!(self.is_consistent(routes), "Tree is inconsistent");
+    }
+
+    /// Thread visits every node once, after its parent, and potentials
+    /// match costs of the tree cells
+    fn is_consistent(&self, routes: &Routes<T>) -> bool {
+        let mut visited = vec![false; self.parent.len()];
+        let mut node = 0;
+        loop {
+            if visited[node] || self.rev_thread[self.thread[node]] != node {
+                return false;
+            }
+            visited[node] = true;
+            if let Some(parent) = self.parent[node] {
+                let (i, j) = self.cell(node, parent);
+                if !visited[parent]
+                    || self.depth[node] != self.depth[parent] + 1
+                    || routes
+                        .cost(i, j)
+                        .compare(&(self.potentials[i] + self.potentials[self.m + j]))
+                        .is_ne()
+                {
+                    return false;
+                }
+            }
+            node = self.thread[node];
+            if node == 0 {
+                return visited.iter().all(|x| *x);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sparse::{Lane, SparseProblem};
+    use rand::prelude::*;
+    use rstest::*;
+
+    type Grid = Routes<i32>;
+
+    /// Routes of the whole grid with the given costs
+    fn grid(costs: &[Vec<i32>]) -> Grid {
+        let (m, n) = (costs.len(), costs[0].len());
+        Routes::new(&SparseProblem {
+            supply: vec![0; m],
+            demand: vec![0; n],
+            lanes: (0..m)
+                .flat_map(|i| (0..n).map(move |j| (i, j)))
+                .map(|(i, j)| Lane {
+                    source: i,
+                    destination: j,
+                    cost: costs[i][j],
+                    capacity: None,
+                })
+                .collect(),
+            sources: vec![],
+            destinations: vec![],
+        })
+    }
+
+    /// Tree after pivots matches the one built from scratch for the same basis
+    fn assert_same(tree: &SpanningTree<i32>, base: &[(usize, usize)], grid: &Grid) {
+        let fresh = SpanningTree::new(tree.m, tree.parent.len() - tree.m, base, grid);
+        assert!(tree.is_consistent(grid), "Tree is inconsistent");
+        assert_eq!(tree.parent, fresh.parent);
+        assert_eq!(tree.depth, fresh.depth);
+        assert_eq!(tree.potentials, fresh.potentials);
+        for node in 0..tree.parent.len() {
+            let (mut a, mut b) = (tree.subtree(node), fresh.subtree(node));
+            assert_eq!(a[0], node);
+            a.sort();
+            b.sort();
+            assert_eq!(a, b);
+        }
+    }
+
+    /// Rows 0 and 1 are nodes 0 and 1, columns 0 and 1 are nodes 2 and 3.
+    /// Row 1 hangs on column 1, which hangs on row 0 along with column 0
+    fn square() -> (Grid, Vec<(usize, usize)>) {
+        (
+            grid(&[vec![1, 2], vec![5, 4]]),
+            vec![(0, 0), (0, 1), (1, 1)],
+        )
+    }
+
+    #[rstest]
+    fn potentials() {
+        let (grid, base) = square();
+        let tree = SpanningTree::new(2, 2, &base, &grid);
+        assert_eq!(tree.potentials, [0, 2, 1, 2].map(Cost::new).to_vec());
+        assert_eq!(tree.parent, vec![None, Some(3), Some(0), Some(0)]);
+        assert_eq!(tree.depth, vec![0, 2, 1, 1]);
+        assert_eq!(tree.child((1, 1)), 1);
+        assert_eq!(tree.child((0, 1)), 3);
+        assert_eq!(tree.subtree(3), vec![3, 1]);
+    }
+
+    #[rstest]
+    fn cycle() {
+        let (grid, base) = square();
+        let tree = SpanningTree::new(2, 2, &base, &grid);
+        // Column side comes first
+        assert_eq!(tree.cycle(1, 0), vec![(1, 0), (0, 0), (0, 1), (1, 1)]);
+
+        let tree = SpanningTree::new(2, 2, &[(0, 0), (1, 0), (1, 1)], &grid);
+        assert_eq!(tree.cycle(0, 1), vec![(0, 1), (1, 1), (1, 0), (0, 0)]);
+    }
+
+    /// Subtree cut off by the leaving cell is re-rooted at the entering one
+    #[rstest]
+    fn pivot_reroots_subtree() {
+        let (grid, mut base) = square();
+        let mut tree = SpanningTree::new(2, 2, &base, &grid);
+        tree.pivot((1, 0), (0, 1), &grid);
+        base[1] = (1, 0);
+
+        // Row 1 hangs on column 0 now, and column 1 hangs on row 1
+        assert_eq!(tree.parent, vec![None, Some(2), Some(0), Some(1)]);
+        assert_eq!(tree.potentials, [0, 4, 1, 0].map(Cost::new).to_vec());
+        assert_same(&tree, &base, &grid);
+    }
+
+    /// Leaving cell connects the row of the entering one to its parent,
+    /// so only that row moves
+    #[rstest]
+    fn pivot_replaces_own_edge() {
+        let (grid, mut base) = square();
+        let mut tree = SpanningTree::new(2, 2, &base, &grid);
+        tree.pivot((1, 0), (1, 1), &grid);
+        base[2] = (1, 0);
+
+        assert_eq!(tree.subtree(1), vec![1]);
+        assert_eq!(tree.parent, vec![None, Some(2), Some(0), Some(0)]);
+        assert_same(&tree, &base, &grid);
+    }
+
+    #[rstest]
+    #[case(1, 5)]
+    #[case(4, 4)]
+    #[case(6, 3)]
+    fn random_pivots(#[case] m: usize, #[case] n: usize) {
+        let mut rng = StdRng::seed_from_u64((m * n) as u64);
+        let grid = grid(
+            &(0..m)
+                .map(|_| (0..n).map(|_| rng.gen_range(0..=20)).collect())
+                .collect::<Vec<_>>(),
+        );
+
+        // Staircase makes a path through all rows and columns
+        let (mut i, mut j) = (0, 0);
+        let mut base = vec![(0, 0)];
+        while (i, j) != (m - 1, n - 1) {
+            match (i + 1 < m && rng.gen_bool(0.5)) || j + 1 == n {
+                true => i += 1,
+                false => j += 1,
+            }
+            base.push((i, j));
+        }
+        let mut tree = SpanningTree::new(m, n, &base, &grid);
+
+        for _ in 0..50 {
+            let entering = (rng.gen_range(0..m), rng.gen_range(0..n));
+            if base.contains(&entering) {
+                continue;
+            }
+            let cycle = tree.cycle(entering.0, entering.1);
+            let leaving = cycle[rng.gen_range(1..cycle.len())];
+            tree.pivot(entering, leaving, &grid);
+            let idx = base.iter().position(|x| *x == leaving).unwrap();
+            base[idx] = entering;
+            assert_same(&tree, &base, &grid);
+        }
+    }
+}
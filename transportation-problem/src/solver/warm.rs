@@ -0,0 +1,329 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::pricing::{Dantzig, Pricing};
+use super::tree::SpanningTree;
+use super::{SolverStats, TransportationSolver};
+use crate::cost::Cost;
+use crate::error::SolverError;
+use crate::number::Number;
+use crate::problem::Dummy;
+use crate::sparse::SparseProblem;
+
+/// Modification of a solved problem
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Change<T = i32> {
+    Cost {
+        source: usize,
+        destination: usize,
+        value: T,
+    },
+    Supply {
+        source: usize,
+        value: T,
+    },
+    Demand {
+        destination: usize,
+        value: T,
+    },
+}
+
+/// Effort of re-optimizing from the previous basis
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WarmStart {
+    /// Pivots of the dual simplex, which made the plan feasible
+    /// after supplies and demands changed
+    pub dual_pivots: usize,
+    /// Iterations of the last solve from scratch, which was of the problem
+    /// before the changes
+    pub cold_iterations: usize,
+}
+
+impl<T: Number> TransportationSolver<T> {
+    /// Number of sources and destinations without the dummy node
+    fn original_size(&self) -> (usize, usize) {
+        (
+            self.m - (self.dummy == Some(Dummy::Source)) as usize,
+            self.n - (self.dummy == Some(Dummy::Destination)) as usize,
+        )
+    }
+
+    /// Problem as it was given, without the dummy node
+    fn original(&self) -> SparseProblem<T> {
+        let (m, n) = self.original_size();
+        SparseProblem {
+            supply: self.problem.supply[..m].to_vec(),
+            demand: self.problem.demand[..n].to_vec(),
+            lanes: self
+                .problem
+                .lanes
+                .iter()
+                .filter(|x| x.source < m && x.destination < n)
+                .copied()
+                .collect(),
+            sources: self.problem.sources.clone(),
+            destinations: self.problem.destinations.clone(),
+        }
+    }
+
+    /// Original problem with the changes applied. Costs of forbidden routes
+    /// are ignored
+    fn changed(&self, changes: &[Change<T>]) -> Result<SparseProblem<T>, SolverError<T>> {
+        let mut problem = self.original();
+        let (m, n) = (problem.supply.len(), problem.demand.len());
+        let lanes = problem
+            .lanes
+            .iter()
+            .enumerate()
+            .map(|(idx, x)| ((x.source, x.destination), idx))
+            .collect::<HashMap<_, _>>();
+        for (idx, change) in changes.iter().copied().enumerate() {
+            match change {
+                Change::Cost {
+                    source,
+                    destination,
+                    value,
+                } if source < m && destination < n => {
+                    if let Some(lane) = lanes.get(&(source, destination)) {
+                        problem.lanes[*lane].cost = value;
+                    }
+                }
+                Change::Supply { source, value } if source < m => problem.supply[source] = value,
+                Change::Demand { destination, value } if destination < n => {
+                    problem.demand[destination] = value
+                }
+                _ => return Err(SolverError::ChangeOutOfBounds { change: idx }),
+            }
+        }
+        problem.validate()?;
+        Ok(problem)
+    }
+
+    /// Solves the problem from scratch, with the balancing of the original one
+    fn cold_solve(&mut self, problem: SparseProblem<T>) -> Result<(), SolverError<T>> {
+        let max_iterations = self.max_iterations;
+        *self = Self::sparse(problem, self.balancing.clone())?;
+        self.max_iterations = max_iterations;
+        self.solve()
+    }
+
+    /// Applies the changes and re-optimizes from the current basis. The basis stays
+    /// optimal for the old costs, so the dual simplex restores feasibility for the
+    /// new supplies and demands, and then the primal one optimizes the new costs.
+    /// The problem is solved from scratch, if it wasn't solved yet, or if the
+    /// imbalance of supply and demand can't be taken by the same dummy node.
+    /// If no plan of the changed problem is reached, the previous one is kept
+    /// along with its stats
+    pub fn resolve(&mut self, changes: &[Change<T>]) -> Result<(), SolverError<T>> {
+        let mut solver = self.clone();
+        let result = solver.reoptimize(changes);
+        // Plan of an infeasible problem is kept, as after solving from scratch
+        if solver.stats.is_some() {
+            *self = solver;
+        }
+        result
+    }
+
+    /// Same as `resolve`, but leaves the solver without stats on failure
+    fn reoptimize(&mut self, changes: &[Change<T>]) -> Result<(), SolverError<T>> {
+        let problem = self.changed(changes)?;
+        let Some(previous) = self.stats.take() else {
+            return self.cold_solve(problem);
+        };
+        let difference = problem.total_supply() - problem.total_demand();
+        let fits = matches!(
+            (self.dummy, difference.compare(&T::zero())),
+            (_, Ordering::Equal)
+                | (Some(Dummy::Source), Ordering::Less)
+                | (Some(Dummy::Destination), Ordering::Greater)
+        );
+        if !fits {
+            return self.cold_solve(problem);
+        }
+
+        // Lanes of the dummy node follow the original ones
+        let (m, n) = (problem.supply.len(), problem.demand.len());
+        for (lane, changed) in self.problem.lanes.iter_mut().zip(problem.lanes) {
+            lane.cost = changed.cost;
+        }
+        self.problem.supply[..m].copy_from_slice(&problem.supply);
+        self.problem.demand[..n].copy_from_slice(&problem.demand);
+        match self.dummy {
+            Some(Dummy::Source) => self.problem.supply[m] = -difference,
+            Some(Dummy::Destination) => self.problem.demand[n] = difference,
+            None => {}
+        }
+
+        let mut iterations = 0;
+        self.basic_flows();
+        self.restore_feasibility(&mut iterations)?;
+        let dual_pivots = iterations;
+
+        for lane in self.problem.lanes.iter() {
+            self.routes[(lane.source, lane.destination)].cost = Cost::new(lane.cost);
+        }
+        self.tree = SpanningTree::new(self.m, self.n, &self.base, &self.routes);
+        let initial_objective = self.objective()?.val;
+        let mut chain_lengths = 0;
+        self.iterate::<Dantzig, _>(&mut iterations, &mut chain_lengths, &mut ())?;
+
+        let feasible = !self.objective()?.is_forbidden();
+        self.finish(SolverStats {
+            feasible,
+            initial: "warm".to_string(),
+            pricing: Dantzig::NAME.to_string(),
+            initial_objective,
+            iterations,
+            avg_chain_len: chain_lengths as f32 / (iterations - dual_pivots) as f32,
+            warm_start: Some(WarmStart {
+                dual_pivots,
+                cold_iterations: previous
+                    .warm_start
+                    .map_or(previous.iterations, |x| x.cold_iterations),
+            }),
+            ..Default::default()
+        })
+    }
+
+    /// Recomputes flows of basic cells from supplies and demands, while non-basic
+    /// cells stay at their bounds. Leaves pass their remaining supply or demand on
+    /// to their parents, so some flows may end up out of their bounds
+    fn basic_flows(&mut self) {
+        let mut remaining = self
+            .problem
+            .supply
+            .iter()
+            .chain(self.problem.demand.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        for cell in self.routes.iter().filter(|x| !x.base) {
+            remaining[cell.source] -= cell.val;
+            remaining[self.m + cell.destination] -= cell.val;
+        }
+
+        for node in self.tree.subtree(0).into_iter().rev() {
+            let Some((i, j)) = self.tree.parent_cell(node) else {
+                continue;
+            };
+            let parent = match node < self.m {
+                true => self.m + j,
+                false => i,
+            };
+            let flow = remaining[node];
+            self.routes[(i, j)].val = flow;
+            remaining[parent] -= flow;
+        }
+    }
+
+    /// Basic cell out of its bounds along with the amount, which brings it back.
+    /// The farthest one is picked, or the first one by Bland's rule
+    fn violated(&self, bland: bool) -> Option<((usize, usize), T)> {
+        let violations = self.base.iter().filter_map(|(i, j)| {
+            let cell = &self.routes[(*i, *j)];
+            match cell.capacity {
+                _ if cell.val.is_negative() => Some(((*i, *j), -cell.val)),
+                Some(capacity) if cell.val.compare(&capacity).is_gt() => {
+                    Some(((*i, *j), capacity - cell.val))
+                }
+                _ => None,
+            }
+        });
+        let magnitude = |x: &T| match x.is_negative() {
+            true => -*x,
+            false => *x,
+        };
+        match bland {
+            true => violations.min_by_key(|(cell, _)| *cell),
+            false => violations.max_by(|(_, a), (_, b)| magnitude(a).compare(&magnitude(b))),
+        }
+    }
+
+    /// Non-basic cell, which replaces the leaving one in the dual simplex. Moving it
+    /// away from its bound must push goods across the cut made by the leaving cell
+    /// so that the leaving one moves back to its bound. Out of such cells the one
+    /// with the smallest reduced cost by absolute value keeps the basis dual feasible.
+    /// `None` if there is no such cell, so the changed problem is infeasible
+    fn dual_entering(&self, (i, j): (usize, usize), increase: bool) -> Option<(usize, usize)> {
+        let child = self.tree.child((i, j));
+        let mut inside = vec![false; self.m + self.n];
+        for node in self.tree.subtree(child) {
+            inside[node] = true;
+        }
+        // Leaving cell must carry more goods into the subtree, or fewer out of it
+        let inwards = inside[self.m + j] == increase;
+
+        self.routes
+            .iter()
+            .filter(|cell| {
+                let (k, l) = (cell.source, self.m + cell.destination);
+                let at_upper = cell.val.is_positive();
+                !cell.base
+                    && inside[k] != inside[l]
+                    // Entering cell must move goods the other way
+                    && (inside[l] == at_upper) == inwards
+                    && (at_upper || cell.capacity.is_none_or(|x| x.is_positive()))
+            })
+            .min_by_key(|cell| match cell.val.is_positive() {
+                true => -self.reduced(cell),
+                false => self.reduced(cell),
+            })
+            .map(|cell| (cell.source, cell.destination))
+    }
+
+    /// Dual simplex, which pivots out basic cells out of their bounds,
+    /// while reduced costs keep their signs
+    fn restore_feasibility(&mut self, iterations: &mut usize) -> Result<(), SolverError<T>> {
+        let mut degenerate_streak = 0;
+        loop {
+            // Ties of reduced costs may cycle, as in the primal simplex
+            let bland = degenerate_streak > self.m + self.n;
+            let Some((leaving, amount)) = self.violated(bland) else {
+                return Ok(());
+            };
+            if self.max_iterations.is_some_and(|x| *iterations >= x) {
+                return Err(SolverError::IterationLimit {
+                    iterations: *iterations,
+                });
+            }
+            *iterations += 1;
+            let Some(entering) = self.dual_entering(leaving, amount.is_positive()) else {
+                return Err(SolverError::Infeasible);
+            };
+            match self.reduced(&self.routes[entering]).is_zero() {
+                true => degenerate_streak += 1,
+                false => degenerate_streak = 0,
+            }
+
+            let chain = self.find_chain(entering);
+            let increase = !self.routes[entering].val.is_positive();
+            let step = match amount.is_negative() {
+                true => -amount,
+                false => amount,
+            };
+            for (idx, (i, j)) in chain.iter().enumerate() {
+                match idx.is_multiple_of(2) == increase {
+                    true => self.routes[(*i, *j)].val += step,
+                    false => self.routes[(*i, *j)].val -= step,
+                }
+            }
+
+            // Leaving cell is exactly at its bound
+            let cell = &mut self.routes[leaving];
+            cell.val = match amount.is_positive() {
+                true => T::zero(),
+                false => cell.capacity.expect("Only bounded cells exceed capacity"),
+            };
+            let base_pos = self
+                .base
+                .iter()
+                .position(|x| *x == leaving)
+                .expect("Inconsistency in bases");
+            self.base[base_pos] = entering;
+            self.routes[entering].base = true;
+            self.routes[leaving].base = false;
+            self.tree.pivot(entering, leaving, &self.routes);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SolverError;
+use crate::number::Number;
+use crate::problem::{Dummy, Penalties, Problem};
+
+/// Route, which can be used
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Lane<T = i32> {
+    pub source: usize,
+    pub destination: usize,
+    pub cost: T,
+    /// `None` if the lane is unbounded, which may be left out
+    pub capacity: Option<T>,
+}
+
+/// Problem storing only the routes, which can be used, so that it takes memory
+/// in proportion to their number. Routes, which are not listed, are forbidden
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SparseProblem<T = i32> {
+    pub supply: Vec<T>,
+    pub demand: Vec<T>,
+    /// Every route is listed at most once
+    pub lanes: Vec<Lane<T>>,
+    /// Names of sources, as in `Problem`
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// Names of destinations, as in `Problem`
+    #[serde(default)]
+    pub destinations: Vec<String>,
+}
+
+impl<T: Number> Problem<T> {
+    /// Same problem storing only the routes, which are not forbidden
+    pub fn sparse(&self) -> SparseProblem<T> {
+        let (m, n) = (self.supply.len(), self.demand.len());
+        let weights = self.weights();
+        let capacities = self.capacity_grid();
+        SparseProblem {
+            supply: self.supply.clone(),
+            demand: self.demand.clone(),
+            lanes: (0..m)
+                .flat_map(|i| (0..n).map(move |j| (i, j)))
+                .filter(|(i, j)| !weights[*i][*j].is_forbidden())
+                .map(|(i, j)| Lane {
+                    source: i,
+                    destination: j,
+                    cost: self.costs[i][j],
+                    capacity: capacities[i][j],
+                })
+                .collect(),
+            sources: self.sources.clone(),
+            destinations: self.destinations.clone(),
+        }
+    }
+}
+
+impl<T: Number> SparseProblem<T> {
+    /// Checks that the data is consistent, so that the problem can be solved
+    pub fn validate(&self) -> Result<(), SolverError<T>> {
+        let (m, n) = (self.supply.len(), self.demand.len());
+        if m == 0 || n == 0 {
+            return Err(SolverError::Empty);
+        }
+        if !self.sources.is_empty() && self.sources.len() != m {
+            return Err(SolverError::DimensionMismatch {
+                what: "source names".to_string(),
+                expected: m,
+                found: self.sources.len(),
+            });
+        }
+        if !self.destinations.is_empty() && self.destinations.len() != n {
+            return Err(SolverError::DimensionMismatch {
+                what: "destination names".to_string(),
+                expected: n,
+                found: self.destinations.len(),
+            });
+        }
+        let mut listed = HashSet::with_capacity(self.lanes.len());
+        for lane in self.lanes.iter() {
+            let (source, destination) = (lane.source, lane.destination);
+            if source >= m || destination >= n {
+                return Err(SolverError::RouteOutOfBounds {
+                    source,
+                    destination,
+                });
+            }
+            if !listed.insert((source, destination)) {
+                return Err(SolverError::DuplicateRoute {
+                    source,
+                    destination,
+                });
+            }
+            if let Some(value) = lane.capacity.filter(|x| x.is_negative()) {
+                return Err(SolverError::NegativeCapacity {
+                    source,
+                    destination,
+                    value,
+                });
+            }
+        }
+        validate_amounts(&self.supply, &self.demand)
+    }
+
+    pub fn total_supply(&self) -> T {
+        self.supply.iter().copied().sum()
+    }
+
+    pub fn total_demand(&self) -> T {
+        self.demand.iter().copied().sum()
+    }
+
+    /// Same problem with a cost matrix, where unlisted routes are forbidden
+    pub fn dense(&self) -> Problem<T> {
+        let (m, n) = (self.supply.len(), self.demand.len());
+        let mut costs = vec![vec![T::zero(); n]; m];
+        let mut listed = vec![vec![false; n]; m];
+        for lane in self.lanes.iter() {
+            costs[lane.source][lane.destination] = lane.cost;
+            listed[lane.source][lane.destination] = true;
+        }
+        Problem {
+            costs,
+            supply: self.supply.clone(),
+            demand: self.demand.clone(),
+            forbidden: (0..m)
+                .flat_map(|i| (0..n).map(move |j| (i, j)))
+                .filter(|(i, j)| !listed[*i][*j])
+                .collect(),
+            capacities: self
+                .lanes
+                .iter()
+                .filter_map(|x| x.capacity.map(|c| (x.source, x.destination, c)))
+                .collect(),
+            sources: self.sources.clone(),
+            destinations: self.destinations.clone(),
+        }
+    }
+
+    /// Returns an equivalent balanced problem with a dummy node taking the
+    /// difference between total supply and demand. Its lanes follow the others
+    pub fn balance(&self, penalties: &Penalties<T>) -> (SparseProblem<T>, Option<Dummy>) {
+        let penalty = |penalties: &[T], i: usize| penalties.get(i).copied().unwrap_or_default();
+        let mut problem = self.clone();
+        let (m, n) = (self.supply.len(), self.demand.len());
+        let difference = self.total_supply() - self.total_demand();
+
+        let dummy = match difference.compare(&T::zero()) {
+            Ordering::Equal => None,
+            // Not enough supply => unmet demand comes from the dummy source
+            Ordering::Less => {
+                problem.lanes.extend((0..n).map(|j| Lane {
+                    source: m,
+                    destination: j,
+                    cost: penalty(&penalties.shortage, j),
+                    capacity: None,
+                }));
+                problem.supply.push(-difference);
+                Some(Dummy::Source)
+            }
+            // Too much supply => leftovers go to the dummy destination
+            Ordering::Greater => {
+                problem.lanes.extend((0..m).map(|i| Lane {
+                    source: i,
+                    destination: n,
+                    cost: penalty(&penalties.surplus, i),
+                    capacity: None,
+                }));
+                problem.demand.push(difference);
+                Some(Dummy::Destination)
+            }
+        };
+
+        (problem, dummy)
+    }
+}
+
+/// Checks that no supply or demand is negative
+pub(crate) fn validate_amounts<T: Number>(
+    supply: &[T],
+    demand: &[T],
+) -> Result<(), SolverError<T>> {
+    if let Some((source, value)) = supply.iter().enumerate().find(|(_, x)| x.is_negative()) {
+        return Err(SolverError::NegativeSupply {
+            source,
+            value: *value,
+        });
+    }
+    if let Some((destination, value)) = demand.iter().enumerate().find(|(_, x)| x.is_negative()) {
+        return Err(SolverError::NegativeDemand {
+            destination,
+            value: *value,
+        });
+    }
+    Ok(())
+}
@@ -1,51 +1,119 @@
 use std::io::Write;
 use std::process::{Command, Stdio};
 
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::certificate::*;
+use crate::cost::Cost;
+use crate::error::SolverError;
+use crate::number::Number;
 use crate::problem::*;
+use crate::solution::Solution;
+use crate::solver::initial::*;
 use crate::solver::*;
+use crate::sparse::*;
 
-fn verify(solver: &TransportationSolver) {
-    // Spawn the command
-    let mut child = Command::new(".venv/bin/python3")
-        .stdin(Stdio::piped())
-        .args(["scripts/check.py"])
-        .spawn()
-        .expect("Failed to start a subprocess");
-
-    // Pass the data
-    child
-        .stdin
-        .as_mut()
-        .expect("Failed to pass data to the checker")
-        .write_all(
-            serde_json::to_string(&solver)
-                .expect("Failed to serialize a solver")
-                .as_bytes(),
-        )
-        .expect("Failed to write to subprocess stdin");
-
-    // Check the status code
-    match child
-        .wait_with_output()
-        .expect("Failed to read stdout")
-        .status
-        .code()
-        .expect("Couldn't retrieve")
-    {
-        0 => (),
-        _ => panic!("Wrong answer"),
+/// Big-M cost of the fixtures, which predate forbidden routes
+const M: i32 = 5_000_000;
+
+fn verify<T: Number>(solver: &TransportationSolver<T>) {
+    let certificate = solver.certificate();
+    for violation in certificate.violations.iter() {
+        println!("{violation}");
     }
+    assert!(certificate.is_valid(), "Wrong answer");
+    assert!(certificate
+        .objective
+        .compare(&certificate.dual_objective)
+        .is_eq());
+    assert!(certificate
+        .objective
+        .compare(&stats(solver).objective)
+        .is_eq());
+}
+
+fn stats<T>(solver: &TransportationSolver<T>) -> &SolverStats<T> {
+    solver.stats.as_ref().expect("Solver has no statistics")
+}
+
+/// Balanced problem solved by the solver
+fn problem_of(solver: &TransportationSolver) -> Problem {
+    serde_json::from_value::<SparseProblem>(
+        serde_json::to_value(solver).expect("Failed to serialize a solver")["problem"].clone(),
+    )
+    .expect("Failed to deserialize a problem")
+    .dense()
+}
+
+/// Goods shipped from every source to every destination of a small problem
+fn plan<T: Number>(solution: &Solution<T>) -> Vec<Vec<T>> {
+    (0..solution.stats.m)
+        .map(|i| {
+            (0..solution.stats.n)
+                .map(|j| solution.shipment(i, j))
+                .collect()
+        })
+        .collect()
+}
+
+fn try_solve<I: InitialSolution>(
+    problem: Problem,
+) -> (TransportationSolver, Result<(), SolverError>) {
+    let mut solver = TransportationSolver::new(problem).expect("Invalid problem");
+    let result = solver.solve_with::<I>();
+    (solver, result)
+}
+
+fn solve_initial<I: InitialSolution>(problem: Problem) -> TransportationSolver {
+    let (solver, result) = try_solve::<I>(problem);
+    result.expect("Failed to solve");
+    solver
 }
 
 fn solve_problem(problem: Problem, check: bool) -> TransportationSolver {
-    let mut solver = TransportationSolver::new(problem);
-    solver.solve();
+    solve_penalized(problem, Penalties::default(), check)
+}
+
+fn solve_penalized(problem: Problem, penalties: Penalties, check: bool) -> TransportationSolver {
+    let mut solver =
+        TransportationSolver::with_penalties(problem, penalties).expect("Invalid problem");
+    solver.solve().expect("Failed to solve");
     if check {
         verify(&solver);
     }
     solver
 }
 
+/// Random problem with costs from 1 to 10 and supplies and demands up to 20.
+/// Routes are forbidden and get capacities up to 10 with the given probabilities
+fn random_problem(
+    rng: &mut StdRng,
+    (m, n): (usize, usize),
+    forbidden: f64,
+    capacitated: f64,
+) -> Problem {
+    let mut problem = Problem {
+        costs: (0..m)
+            .map(|_| (0..n).map(|_| rng.gen_range(1..=10)).collect())
+            .collect(),
+        supply: (0..m).map(|_| rng.gen_range(0..=20)).collect(),
+        demand: (0..n).map(|_| rng.gen_range(0..=20)).collect(),
+        ..Default::default()
+    };
+    for i in 0..m {
+        for j in 0..n {
+            if rng.gen_bool(forbidden) {
+                problem.forbidden.push((i, j));
+            }
+            if rng.gen_bool(capacitated) {
+                problem.capacities.push((i, j, rng.gen_range(0..=10)));
+            }
+        }
+    }
+    problem
+}
+
 pub fn solve_exercise(check: bool) -> TransportationSolver {
     solve_problem(
         Problem {
@@ -57,6 +125,7 @@ pub fn solve_exercise(check: bool) -> TransportationSolver {
             ],
             supply: vec![30, 20, 80, 80],
             demand: vec![40, 40, 20, 110],
+            ..Default::default()
         },
         check,
     )
@@ -73,6 +142,7 @@ pub fn solve_former_no_path(check: bool) -> TransportationSolver {
             ],
             supply: vec![1, 6, 10, 8],
             demand: vec![M, 1, 4, 13],
+            ..Default::default()
         },
         check,
     )
@@ -89,6 +159,67 @@ pub fn solve_former_not_optimal(check: bool) -> TransportationSolver {
             ],
             supply: vec![1, 6, 10, 8],
             demand: vec![6, 2, 4, 13],
+            ..Default::default()
+        },
+        check,
+    )
+}
+
+pub fn solve_wide(check: bool) -> TransportationSolver {
+    solve_problem(
+        Problem {
+            costs: vec![
+                vec![4, 8, 8, 6, 5],
+                vec![16, 24, 16, 12, 9],
+                vec![8, 16, 24, 10, 7],
+            ],
+            supply: vec![76, 82, 77],
+            demand: vec![72, 102, 41, 10, 10],
+            ..Default::default()
+        },
+        check,
+    )
+}
+
+pub fn solve_tall(check: bool) -> TransportationSolver {
+    solve_problem(
+        Problem {
+            costs: vec![vec![3, 1], vec![2, 5], vec![4, 4], vec![1, 6]],
+            supply: vec![10, 20, 15, 5],
+            demand: vec![30, 20],
+            ..Default::default()
+        },
+        check,
+    )
+}
+
+pub fn solve_shortage(check: bool) -> TransportationSolver {
+    solve_penalized(
+        Problem {
+            costs: vec![vec![2, 3], vec![4, 1]],
+            supply: vec![10, 10],
+            demand: vec![15, 15],
+            ..Default::default()
+        },
+        Penalties {
+            shortage: vec![5, 0],
+            surplus: vec![],
+        },
+        check,
+    )
+}
+
+pub fn solve_surplus(check: bool) -> TransportationSolver {
+    solve_penalized(
+        Problem {
+            costs: vec![vec![1, 2], vec![3, 1], vec![2, 2]],
+            supply: vec![10, 10, 10],
+            demand: vec![8, 12],
+            ..Default::default()
+        },
+        Penalties {
+            shortage: vec![],
+            surplus: vec![0, 0, 4],
         },
         check,
     )
@@ -115,6 +246,22 @@ mod check {
     fn former_not_optimal() {
         solve_former_not_optimal(true);
     }
+    #[rstest]
+    fn wide() {
+        solve_wide(true);
+    }
+    #[rstest]
+    fn tall() {
+        solve_tall(true);
+    }
+    #[rstest]
+    fn shortage() {
+        solve_shortage(true);
+    }
+    #[rstest]
+    fn surplus() {
+        solve_surplus(true);
+    }
 
     #[rstest]
     #[case(6)]
@@ -144,6 +291,22 @@ mod perf {
     fn former_not_optimal() {
         solve_former_not_optimal(false);
     }
+    #[rstest]
+    fn wide() {
+        solve_wide(false);
+    }
+    #[rstest]
+    fn tall() {
+        solve_tall(false);
+    }
+    #[rstest]
+    fn shortage() {
+        solve_shortage(false);
+    }
+    #[rstest]
+    fn surplus() {
+        solve_surplus(false);
+    }
 
     #[rstest]
     #[case(6)]
@@ -157,18 +320,3147 @@ mod perf {
     }
 }
 
+#[cfg(test)]
+mod validation {
+    use super::*;
+    use rstest::*;
+
+    fn problem() -> Problem {
+        Problem {
+            costs: vec![vec![1, 2, 3], vec![4, 5, 6]],
+            supply: vec![5, 5],
+            demand: vec![3, 3, 4],
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    fn ragged_costs() {
+        let mut problem = problem();
+        problem.costs[1].pop();
+        assert_eq!(
+            TransportationSolver::new(problem).err(),
+            Some(SolverError::DimensionMismatch {
+                what: "cost columns".to_string(),
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+    #[rstest]
+    fn missing_row() {
+        let mut problem = problem();
+        problem.costs.pop();
+        assert_eq!(
+            TransportationSolver::new(problem).err(),
+            Some(SolverError::DimensionMismatch {
+                what: "cost rows".to_string(),
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+    #[rstest]
+    fn empty() {
+        let problem = Problem {
+            costs: vec![vec![], vec![]],
+            supply: vec![5, 5],
+            demand: vec![],
+            ..Default::default()
+        };
+        assert_eq!(
+            TransportationSolver::new(problem).err(),
+            Some(SolverError::Empty)
+        );
+    }
+    #[rstest]
+    fn route_out_of_bounds() {
+        let mut problem = problem();
+        problem.forbidden.push((1, 3));
+        assert_eq!(
+            TransportationSolver::new(problem).err(),
+            Some(SolverError::RouteOutOfBounds {
+                source: 1,
+                destination: 3
+            })
+        );
+    }
+    #[rstest]
+    fn capacity_out_of_bounds() {
+        let mut problem = problem();
+        problem.capacities.push((2, 0, 5));
+        assert_eq!(
+            TransportationSolver::new(problem).err(),
+            Some(SolverError::RouteOutOfBounds {
+                source: 2,
+                destination: 0
+            })
+        );
+    }
+    #[rstest]
+    fn negative_capacity() {
+        let mut problem = problem();
+        problem.capacities.push((1, 2, -1));
+        assert_eq!(
+            TransportationSolver::new(problem).err(),
+            Some(SolverError::NegativeCapacity {
+                source: 1,
+                destination: 2,
+                value: -1
+            })
+        );
+    }
+    #[rstest]
+    fn negative_supply() {
+        let mut problem = problem();
+        problem.supply = vec![-5, 15];
+        assert_eq!(
+            TransportationSolver::new(problem).err(),
+            Some(SolverError::NegativeSupply {
+                source: 0,
+                value: -5
+            })
+        );
+    }
+    #[rstest]
+    fn negative_demand() {
+        let mut problem = problem();
+        problem.demand = vec![3, 8, -1];
+        assert_eq!(
+            TransportationSolver::new(problem).err(),
+            Some(SolverError::NegativeDemand {
+                destination: 2,
+                value: -1
+            })
+        );
+    }
+    #[rstest]
+    fn unbalanced() {
+        let mut problem = problem();
+        problem.supply = vec![5, 6];
+        assert_eq!(
+            TransportationSolver::with_balancing(problem.clone(), Balancing::Strict).err(),
+            Some(SolverError::Unbalanced {
+                supply: 11,
+                demand: 10
+            })
+        );
+        assert!(TransportationSolver::with_balancing(problem, Balancing::default()).is_ok());
+    }
+    #[rstest]
+    fn too_many_penalties() {
+        let penalties = Penalties {
+            shortage: vec![1, 2, 3, 4],
+            surplus: vec![],
+        };
+        assert_eq!(
+            TransportationSolver::with_penalties(problem(), penalties).err(),
+            Some(SolverError::DimensionMismatch {
+                what: "shortage penalties".to_string(),
+                expected: 3,
+                found: 4
+            })
+        );
+    }
+    #[rstest]
+    fn iteration_limit() {
+        let mut solver = TransportationSolver::new(Problem {
+            costs: vec![vec![4, 1], vec![1, 4]],
+            supply: vec![5, 5],
+            demand: vec![5, 5],
+            ..Default::default()
+        })
+        .unwrap();
+        solver.max_iterations = Some(0);
+        assert_eq!(
+            solver.solve(),
+            Err(SolverError::IterationLimit { iterations: 0 })
+        );
+        solver.max_iterations = None;
+        assert_eq!(solver.solve(), Ok(()));
+    }
+}
+
+#[cfg(test)]
+mod balance {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn shortage() {
+        let solver = solve_shortage(false);
+        assert_eq!(stats(&solver).objective, 45);
+        assert_eq!(stats(&solver).unmet_demand, vec![0, 10]);
+        assert_eq!(stats(&solver).leftover_supply, vec![0, 0]);
+    }
+    #[rstest]
+    fn surplus() {
+        let solver = solve_surplus(false);
+        let leftover = &stats(&solver).leftover_supply;
+        assert_eq!(stats(&solver).objective, 30);
+        assert_eq!(stats(&solver).unmet_demand, vec![0, 0]);
+        assert_eq!(leftover[2], 0);
+        assert_eq!(leftover.iter().sum::<i32>(), 10);
+    }
+    #[rstest]
+    fn balanced() {
+        let solver = solve_exercise(false);
+        assert_eq!(stats(&solver).unmet_demand, vec![0; 4]);
+        assert_eq!(stats(&solver).leftover_supply, vec![0; 4]);
+    }
+}
+
+#[cfg(test)]
+mod initial {
+    use super::*;
+    use rstest::*;
+
+    fn samples() -> Vec<TransportationSolver> {
+        vec![
+            solve_exercise(false),
+            solve_former_not_optimal(false),
+            solve_wide(false),
+            solve_tall(false),
+            solve_shortage(false),
+            solve_surplus(false),
+            solve_generated(30, false),
+        ]
+    }
+
+    #[rstest]
+    #[case(solve_initial::<NorthWest>, NorthWest::NAME)]
+    #[case(solve_initial::<LeastCost>, LeastCost::NAME)]
+    #[case(solve_initial::<Vogel>, Vogel::NAME)]
+    #[case(solve_initial::<Russell>, Russell::NAME)]
+    fn same_optimum(#[case] solve: fn(Problem) -> TransportationSolver, #[case] name: &str) {
+        for expected in samples() {
+            let solver = solve(problem_of(&expected));
+            assert_eq!(stats(&solver).initial, name);
+            assert_eq!(stats(&solver).objective, stats(&expected).objective);
+            assert!(stats(&solver).initial_objective >= stats(&solver).objective);
+        }
+    }
+
+    #[rstest]
+    fn exercise_starting_points() {
+        let problem = || Problem {
+            costs: vec![
+                vec![19, 30, 50, 10],
+                vec![70, 30, 40, 60],
+                vec![40, 8, 70, 20],
+            ],
+            supply: vec![7, 9, 18],
+            demand: vec![5, 8, 7, 14],
+            ..Default::default()
+        };
+        let initial = |solver: TransportationSolver| stats(&solver).initial_objective;
+        assert_eq!(initial(solve_initial::<NorthWest>(problem())), 1015);
+        assert_eq!(initial(solve_initial::<LeastCost>(problem())), 814);
+        assert_eq!(initial(solve_initial::<Vogel>(problem())), 779);
+    }
+}
+
+#[cfg(test)]
+mod pricing {
+    use super::*;
+    use crate::solver::pricing::*;
+    use rstest::*;
+
+    fn solve_pricing<P: Pricing>(problem: Problem) -> TransportationSolver {
+        let mut solver = TransportationSolver::new(problem).expect("Invalid problem");
+        solver
+            .solve_using::<NorthWest, P>()
+            .expect("Failed to solve");
+        verify(&solver);
+        solver
+    }
+
+    fn gains(gains: &[i32]) -> impl Fn(usize) -> Option<Cost<i32>> + '_ {
+        |idx| (gains[idx] > 0).then(|| Cost::new(gains[idx]))
+    }
+
+    #[rstest]
+    #[case(solve_pricing::<Dantzig>, Dantzig::NAME)]
+    #[case(solve_pricing::<FirstImproving>, FirstImproving::NAME)]
+    #[case(solve_pricing::<Partial>, Partial::NAME)]
+    #[case(solve_pricing::<Bland>, Bland::NAME)]
+    fn same_optimum(#[case] solve: fn(Problem) -> TransportationSolver, #[case] name: &str) {
+        for expected in [
+            solve_exercise(false),
+            solve_former_not_optimal(false),
+            solve_wide(false),
+            solve_tall(false),
+            solve_shortage(false),
+            solve_surplus(false),
+            solve_generated(50, false),
+        ] {
+            let solver = solve(problem_of(&expected));
+            assert_eq!(stats(&solver).pricing, name);
+            assert_eq!(stats(&solver).objective, stats(&expected).objective);
+        }
+    }
+
+    #[rstest]
+    fn default_rule() {
+        let solver = solve_exercise(false);
+        assert_eq!(stats(&solver).pricing, Dantzig::NAME);
+    }
+
+    #[rstest]
+    #[case(solve_pricing::<Dantzig>)]
+    #[case(solve_pricing::<FirstImproving>)]
+    #[case(solve_pricing::<Partial>)]
+    #[case(solve_pricing::<Bland>)]
+    fn degenerate(#[case] solve: fn(Problem) -> TransportationSolver) {
+        let n = 12;
+        let problem = Problem {
+            costs: (0..n)
+                .map(|i| (0..n).map(|j| ((i * j) % 3) as i32).collect())
+                .collect(),
+            supply: vec![1; n],
+            demand: vec![1; n],
+            ..Default::default()
+        };
+        let expected = solve_problem(problem.clone(), true);
+        assert_eq!(stats(&solve(problem)).objective, stats(&expected).objective);
+    }
+
+    #[rstest]
+    #[case(0, 1, 2)]
+    #[case(3, 4, 4)]
+    #[case(5, 7, 7)]
+    #[case(8, 1, 1)]
+    fn rules(#[case] start: usize, #[case] first: usize, #[case] partial: usize) {
+        let values = [0, 1, 4, 0, 3, 0, 0, 2, 0];
+        assert_eq!(Dantzig::pick(9, start, gains(&values)), Some(2));
+        assert_eq!(Bland::pick(9, start, gains(&values)), Some(1));
+        assert_eq!(FirstImproving::pick(9, start, gains(&values)), Some(first));
+        // Blocks of 3 cells starting from `start`
+        assert_eq!(Partial::pick(9, start, gains(&values)), Some(partial));
+        assert_eq!(Partial::pick(9, start, gains(&[0; 9])), None);
+    }
+}
+
+#[cfg(test)]
+mod observer {
+    use super::*;
+    use crate::solver::observer::*;
+    use crate::solver::pricing::*;
+    use rstest::*;
+
+    fn record(problem: Problem) -> (TransportationSolver, Recorder) {
+        let mut solver = TransportationSolver::new(problem).expect("Invalid problem");
+        let mut recorder = Recorder::default();
+        solver
+            .solve_observed::<NorthWest, Dantzig, _>(&mut recorder)
+            .expect("Failed to solve");
+        (solver, recorder)
+    }
+
+    #[rstest]
+    fn history() {
+        let (solver, recorder) = record(problem_of(&solve_exercise(false)));
+        let steps = &recorder.steps;
+        let costs = problem_of(&solver).weights();
+        assert!(steps[0].pivot.is_none());
+        assert_eq!(steps.len(), stats(&solver).iterations);
+        assert_eq!(
+            steps[0].snapshot.objective.val,
+            stats(&solver).initial_objective
+        );
+
+        let last = &steps.last().unwrap().snapshot;
+        let shipments = last
+            .cells
+            .iter()
+            .map(|x| (x.source, x.destination, x.amount))
+            .collect::<Vec<_>>();
+        let solution = solver.solution().unwrap();
+        assert!(shipments
+            .iter()
+            .zip(solution.shipments.iter())
+            .all(|(a, b)| *a == (b.source, b.destination, b.amount)));
+        assert_eq!(shipments.len(), solution.shipments.len());
+        assert_eq!(last.objective.val, stats(&solver).objective);
+        assert!(last
+            .cells
+            .iter()
+            .filter_map(|x| x.reduced_cost)
+            .all(|x| x >= Cost::default()));
+
+        for (previous, step) in steps.iter().zip(steps.iter().skip(1)) {
+            let pivot = step.pivot.as_ref().unwrap();
+            let snapshot = &step.snapshot;
+            assert_eq!(pivot.chain[0], pivot.entering);
+            assert!(snapshot.objective <= previous.snapshot.objective);
+            assert!(previous
+                .snapshot
+                .cells
+                .iter()
+                .find(|x| (x.source, x.destination) == pivot.entering)
+                .and_then(|x| x.reduced_cost)
+                .is_some_and(|x| x < Cost::default()));
+            assert!(snapshot.basis.contains(&pivot.entering));
+            assert!(!snapshot.basis.contains(&pivot.leaving));
+            for (i, j) in snapshot.basis.iter() {
+                assert_eq!(snapshot.u[*i] + snapshot.v[*j], costs[*i][*j]);
+            }
+        }
+    }
+
+    #[rstest]
+    fn artificial() {
+        let (solver, recorder) = record(Problem {
+            costs: vec![vec![1, 2], vec![2, 1]],
+            supply: vec![10, 10],
+            demand: vec![10, 10],
+            capacities: vec![(0, 0, 4)],
+            ..Default::default()
+        });
+        assert_eq!(stats(&solver).initial, "artificial");
+        let initial = recorder
+            .steps
+            .iter()
+            .filter(|x| x.pivot.is_none())
+            .map(|x| x.snapshot.u.len())
+            .collect::<Vec<_>>();
+        assert_eq!(initial, vec![3, 2]);
+    }
+
+    #[rstest]
+    fn serializable() {
+        let (_, recorder) = record(problem_of(&solve_wide(false)));
+        let json = serde_json::to_string(&recorder).unwrap();
+        let restored: Recorder = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.steps.len(), recorder.steps.len());
+    }
+
+    #[rstest]
+    fn custom() {
+        struct Amounts(Vec<i32>);
+        impl Observer<i32> for Amounts {
+            fn pivot(&mut self, _solver: &TransportationSolver, pivot: &Pivot) {
+                self.0.push(pivot.amount);
+            }
+        }
+
+        let mut solver = TransportationSolver::new(problem_of(&solve_tall(false))).unwrap();
+        let mut amounts = Amounts(vec![]);
+        solver
+            .solve_observed::<NorthWest, Bland, _>(&mut amounts)
+            .unwrap();
+        assert_eq!(amounts.0.len(), stats(&solver).iterations - 1);
+        assert!(amounts.0.iter().all(|x| *x >= 0));
+    }
+
+    #[rstest]
+    fn before_solving() {
+        let solver = TransportationSolver::new(problem_of(&solve_tall(false))).unwrap();
+        assert!(solver.snapshot().is_none());
+    }
+}
+
+#[cfg(test)]
+mod render {
+    use super::*;
+    use crate::render::*;
+    use crate::solver::observer::*;
+    use crate::solver::pricing::*;
+    use rstest::*;
+
+    fn snapshot() -> Snapshot {
+        let solver = solve_problem(
+            Problem {
+                costs: vec![vec![1, 2], vec![3, 1]],
+                supply: vec![10, 10],
+                demand: vec![5, 15],
+                forbidden: vec![(1, 0)],
+                ..Default::default()
+            },
+            true,
+        );
+        solver.snapshot().unwrap()
+    }
+
+    #[rstest]
+    #[case(Cost::new(-3), "-3")]
+    #[case(Cost::forbidden(), "M")]
+    #[case(-Cost::forbidden(), "-M")]
+    #[case(Cost { big: 2, val: -3 }, "2M-3")]
+    #[case(Cost { big: -1, val: 4 }, "-M+4")]
+    fn display(#[case] cost: Cost, #[case] expected: &str) {
+        assert_eq!(cost.to_string(), expected);
+    }
+
+    #[rstest]
+    fn plain_text() {
+        assert_eq!(
+            PlainText::render(&snapshot()),
+            "        D1         D2          Supply  u\n\
+             S1      [c=1 x=5]  [c=2 x=5]   10      0\n\
+             S2      c=M d=M    [c=1 x=10]  10      -1\n\
+             Demand  5          15          20\n\
+             v       1          2\n\
+             Objective: 25\n"
+        );
+    }
+
+    #[rstest]
+    fn markdown() {
+        assert_eq!(
+            Markdown::render(&snapshot()),
+            "|  | D1 | D2 | Supply | u |\n\
+             |---|---|---|---|---|\n\
+             | S1 | **c=1 x=5** | **c=2 x=5** | 10 | 0 |\n\
+             | S2 | c=M d=M | **c=1 x=10** | 10 | -1 |\n\
+             | Demand | 5 | 15 | 20 |  |\n\
+             | v | 1 | 2 |  |  |\n\
+             \n\
+             Objective: 25\n"
+        );
+    }
+
+    #[rstest]
+    fn latex() {
+        assert_eq!(
+            Latex::render(&snapshot()),
+            "\\begin{tabular}{l|cc|c|c}\n \
+             & D1 & D2 & Supply & u \\\\\n\
+             \\hline\n\
+             S1 & \\textbf{c=1 x=5} & \\textbf{c=2 x=5} & 10 & 0 \\\\\n\
+             S2 & c=M d=M & \\textbf{c=1 x=10} & 10 & -1 \\\\\n\
+             \\hline\n\
+             Demand & 5 & 15 & 20 &  \\\\\n\
+             v & 1 & 2 &  &  \\\\\n\
+             \\end{tabular}\n"
+        );
+    }
+
+    /// Every step of the history can be rendered, including the artificial ones
+    #[rstest]
+    fn history() {
+        let mut solver = TransportationSolver::new(Problem {
+            costs: vec![vec![1, 2], vec![2, 1]],
+            supply: vec![10, 10],
+            demand: vec![10, 10],
+            capacities: vec![(0, 0, 4)],
+            ..Default::default()
+        })
+        .unwrap();
+        let mut recorder = Recorder::default();
+        solver
+            .solve_observed::<NorthWest, Dantzig, _>(&mut recorder)
+            .unwrap();
+        for step in recorder.steps.iter() {
+            let rows = step.snapshot.u.len();
+            assert_eq!(PlainText::render(&step.snapshot).lines().count(), rows + 4);
+            assert_eq!(Markdown::render(&step.snapshot).lines().count(), rows + 6);
+            assert_eq!(Latex::render(&step.snapshot).lines().count(), rows + 7);
+        }
+    }
+}
+
+#[cfg(test)]
+mod degeneracy {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use rstest::*;
+
+    /// North-west corner solution without its zero-valued cells
+    struct Sparse;
+    impl InitialSolution for Sparse {
+        const NAME: &'static str = "sparse";
+
+        fn allocate<T: Number>(
+            problem: &SparseProblem<T>,
+            costs: &Costs<T>,
+        ) -> Vec<(usize, usize, T)> {
+            NorthWest::allocate(problem, costs)
+                .into_iter()
+                .filter(|(_, _, val)| val.is_positive())
+                .collect()
+        }
+    }
+
+    fn permutations(n: usize) -> Vec<Vec<usize>> {
+        match n {
+            0 => vec![vec![]],
+            _ => permutations(n - 1)
+                .into_iter()
+                .flat_map(|p| {
+                    (0..n).map(move |k| {
+                        let mut p = p.clone();
+                        p.insert(k, n - 1);
+                        p
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Every row and column is exhausted at the same time
+    fn assignment(n: usize, seed: u64) -> (Problem, i32) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let costs = (0..n)
+            .map(|_| (0..n).map(|_| rng.gen_range(0..10)).collect::<Vec<i32>>())
+            .collect::<Vec<_>>();
+        let optimum = permutations(n)
+            .iter()
+            .map(|p| p.iter().enumerate().map(|(i, j)| costs[i][*j]).sum())
+            .min()
+            .unwrap();
+        let problem = Problem {
+            costs,
+            supply: vec![1; n],
+            demand: vec![1; n],
+            ..Default::default()
+        };
+        (problem, optimum)
+    }
+
+    #[rstest]
+    fn completes_sparse_basis() {
+        let problem = Problem {
+            costs: vec![vec![1, 2, 3], vec![4, 1, 2], vec![3, 4, 1]],
+            supply: vec![10, 10, 10],
+            demand: vec![10, 10, 10],
+            ..Default::default()
+        };
+        let weights = problem.weights();
+        let costs = |i: usize, j: usize| weights[i][j];
+        assert_eq!(Sparse::allocate(&problem.sparse(), &costs).len(), 3);
+        let solver = solve_initial::<Sparse>(problem);
+        assert_eq!(stats(&solver).objective, 30);
+    }
+
+    #[rstest]
+    #[case(solve_initial::<NorthWest>)]
+    #[case(solve_initial::<LeastCost>)]
+    #[case(solve_initial::<Vogel>)]
+    #[case(solve_initial::<Russell>)]
+    #[case(solve_initial::<Sparse>)]
+    fn assignments(#[case] solve: fn(Problem) -> TransportationSolver) {
+        for seed in 0..20 {
+            let (problem, optimum) = assignment(6, seed);
+            let solver = solve(problem);
+            assert_eq!(stats(&solver).objective, optimum);
+        }
+    }
+}
+
+#[cfg(test)]
+mod forbidden {
+    use super::*;
+    use rstest::*;
+
+    /// Replaces big-M costs with forbidden routes
+    fn forbid_m(mut problem: Problem) -> Problem {
+        for (i, row) in problem.costs.iter_mut().enumerate() {
+            for (j, cost) in row.iter_mut().enumerate() {
+                if *cost == M {
+                    problem.forbidden.push((i, j));
+                    *cost = 0;
+                }
+            }
+        }
+        problem
+    }
+
+    #[rstest]
+    fn exercise() {
+        let expected = solve_exercise(false);
+        let problem = problem_of(&expected);
+        let solver = solve_problem(forbid_m(problem), false);
+        assert_eq!(stats(&solver).objective, stats(&expected).objective);
+    }
+
+    #[rstest]
+    fn starts_on_forbidden_route() {
+        let solver = solve_problem(
+            Problem {
+                costs: vec![vec![0, 4, 2], vec![3, 1, 5]],
+                supply: vec![10, 20],
+                demand: vec![10, 10, 10],
+                forbidden: vec![(0, 0)],
+                ..Default::default()
+            },
+            false,
+        );
+        assert_eq!(stats(&solver).objective, 60);
+    }
+
+    #[rstest]
+    fn unreachable_destination() {
+        let (solver, result) = try_solve::<NorthWest>(Problem {
+            costs: vec![vec![1, 2], vec![3, 4]],
+            supply: vec![5, 5],
+            demand: vec![5, 5],
+            forbidden: vec![(0, 0), (1, 0)],
+            ..Default::default()
+        });
+        assert_eq!(result, Err(SolverError::Infeasible));
+        assert!(!stats(&solver).feasible);
+    }
+
+    #[rstest]
+    fn unreachable_subset() {
+        // Destinations 0 and 1 can only be served by source 0,
+        // which doesn't have enough supply
+        let (_, result) = try_solve::<NorthWest>(Problem {
+            costs: vec![vec![1, 1, 1], vec![1, 1, 1], vec![1, 1, 1]],
+            supply: vec![5, 10, 10],
+            demand: vec![5, 5, 15],
+            forbidden: vec![(1, 0), (1, 1), (2, 0), (2, 1)],
+            ..Default::default()
+        });
+        assert_eq!(result, Err(SolverError::Infeasible));
+    }
+
+    #[rstest]
+    #[case(try_solve::<NorthWest>)]
+    #[case(try_solve::<LeastCost>)]
+    #[case(try_solve::<Vogel>)]
+    #[case(try_solve::<Russell>)]
+    fn generated(#[case] solve: fn(Problem) -> (TransportationSolver, Result<(), SolverError>)) {
+        for n in [6, 10, 30].into_iter().cycle().take(30) {
+            let problem = GenConfig {
+                forbid_m_vals: false,
+                ..Default::default()
+            }
+            .gen(n);
+            let (expected, _) = solve(problem.clone());
+            let (solver, result) = solve(forbid_m(problem));
+            let objective = stats(&expected).objective;
+            if objective < M {
+                assert_eq!(result, Ok(()));
+                assert_eq!(stats(&solver).objective, objective);
+            } else {
+                assert_eq!(result, Err(SolverError::Infeasible));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod number {
+    use super::*;
+    use num_rational::Rational64;
+    use rstest::*;
+
+    fn solve_as<T: Number>(problem: Problem<T>) -> TransportationSolver<T> {
+        let mut solver = TransportationSolver::new(problem).expect("Invalid problem");
+        solver.solve().expect("Failed to solve");
+        solver
+    }
+
+    fn same_optimum<T: Number>() {
+        for expected in [
+            solve_exercise(false),
+            solve_former_not_optimal(false),
+            solve_wide(false),
+            solve_tall(false),
+            solve_shortage(false),
+            solve_surplus(false),
+            solve_generated(30, false),
+        ] {
+            let solver = solve_as(problem_of(&expected).convert::<T>());
+            let objective = T::from_i32(stats(&expected).objective);
+            assert!(stats(&solver).objective.compare(&objective).is_eq());
+        }
+    }
+
+    #[rstest]
+    fn same_optimum_i64() {
+        same_optimum::<i64>();
+    }
+    #[rstest]
+    fn same_optimum_f64() {
+        same_optimum::<f64>();
+    }
+    #[rstest]
+    fn same_optimum_rational() {
+        same_optimum::<Rational64>();
+    }
+
+    #[rstest]
+    fn large_volumes() {
+        let solver = solve_as(Problem::<i64> {
+            costs: vec![vec![1, 2], vec![3, 1]],
+            supply: vec![3_000_000_000, 3_000_000_000],
+            demand: vec![3_000_000_000, 3_000_000_000],
+            ..Default::default()
+        });
+        assert_eq!(stats(&solver).objective, 6_000_000_000);
+    }
+
+    #[rstest]
+    fn fractional_costs() {
+        let solver = solve_as(Problem::<f64> {
+            costs: vec![vec![0.5, 1.25], vec![2.0, 0.75]],
+            supply: vec![1.5, 2.5],
+            demand: vec![2.0, 2.0],
+            ..Default::default()
+        });
+        assert!((stats(&solver).objective - 3.25).abs() < 1e-9);
+    }
+
+    #[rstest]
+    fn exact_rational() {
+        let r = |numer, denom| Rational64::new(numer, denom);
+        let solver = solve_as(Problem {
+            costs: vec![vec![r(1, 2), r(5, 4)], vec![r(2, 1), r(3, 4)]],
+            supply: vec![r(3, 2), r(5, 2)],
+            demand: vec![r(2, 1), r(2, 1)],
+            ..Default::default()
+        });
+        assert_eq!(stats(&solver).objective, r(13, 4));
+    }
+
+    #[rstest]
+    fn unbalanced_fractions() {
+        // Supply exceeds demand only by a fraction
+        let solver = solve_as(Problem::<f64> {
+            costs: vec![vec![1.0, 3.0], vec![2.0, 1.0]],
+            supply: vec![2.25, 1.0],
+            demand: vec![2.0, 1.0],
+            ..Default::default()
+        });
+        assert!((stats(&solver).objective - 3.0).abs() < 1e-9);
+        assert!((stats(&solver).leftover_supply[0] - 0.25).abs() < 1e-9);
+    }
+
+    /// Neighbours are equal within the tolerance, but the ends of the chain
+    /// are not, so only the exact order is transitive
+    #[rstest]
+    fn exact_order_f64() {
+        let costs = [0.0, 0.6e-9, 1.2e-9].map(Cost::new);
+        assert!(costs[0].compare(&costs[1]).is_eq() && costs[1].compare(&costs[2]).is_eq());
+        assert!(costs[0].compare(&costs[2]).is_lt());
+        assert!(costs[0] < costs[1] && costs[1] < costs[2]);
+
+        let mut sorted = vec![costs[2], costs[0], costs[1]];
+        sorted.sort();
+        assert_eq!(sorted, costs);
+    }
+
+    #[rstest]
+    fn overflow() {
+        let problem = Problem {
+            costs: vec![vec![M, M], vec![M, M]],
+            supply: vec![500, 500],
+            demand: vec![500, 500],
+            ..Default::default()
+        };
+        let mut solver = TransportationSolver::new(problem.clone()).unwrap();
+        assert_eq!(solver.solve(), Err(SolverError::Overflow));
+        let solver = solve_as(problem.convert::<i64>());
+        assert_eq!(stats(&solver).objective, 5_000_000_000);
+    }
+}
+
+#[cfg(test)]
+mod certificate {
+    use super::*;
+    use crate::solution::Route;
+    use rstest::*;
+
+    fn problem() -> Problem {
+        Problem {
+            costs: vec![vec![1, 3], vec![2, 1]],
+            supply: vec![5, 5],
+            demand: vec![4, 6],
+            forbidden: vec![(1, 0)],
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    fn optimal() {
+        let certificate =
+            Certificate::check(&problem(), &[vec![4, 1], vec![0, 5]], &[0, -2], &[1, 3]);
+        assert_eq!(certificate.violations, vec![]);
+        assert_eq!(certificate.objective, 12);
+        assert_eq!(certificate.dual_objective, 12);
+    }
+
+    #[rstest]
+    fn infeasible_plan() {
+        let certificate =
+            Certificate::check(&problem(), &[vec![6, -1], vec![1, 4]], &[0, -2], &[1, 3]);
+        assert_eq!(
+            certificate.violations,
+            vec![
+                Violation::Demand {
+                    destination: 0,
+                    expected: 4,
+                    found: 7
+                },
+                Violation::Demand {
+                    destination: 1,
+                    expected: 6,
+                    found: 3
+                },
+                Violation::NegativeFlow {
+                    source: 0,
+                    destination: 1,
+                    flow: -1
+                },
+                Violation::ForbiddenFlow {
+                    source: 1,
+                    destination: 0,
+                    flow: 1
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    fn not_optimal() {
+        let certificate =
+            Certificate::check(&problem(), &[vec![0, 5], vec![4, 1]], &[0, 0], &[1, 3]);
+        assert_eq!(
+            certificate.violations,
+            vec![
+                Violation::ForbiddenFlow {
+                    source: 1,
+                    destination: 0,
+                    flow: 4
+                },
+                Violation::NegativeReducedCost {
+                    source: 1,
+                    destination: 1,
+                    reduced_cost: -2
+                },
+            ]
+        );
+        let certificate =
+            Certificate::check(&problem(), &[vec![3, 2], vec![1, 4]], &[0, -2], &[1, 2]);
+        assert!(certificate
+            .violations
+            .contains(&Violation::ComplementarySlackness {
+                source: 0,
+                destination: 1,
+                flow: 2,
+                reduced_cost: 1
+            }));
+        assert_eq!(
+            certificate.violations[0].to_string(),
+            "Route from 0 to 1 is used for 2, but its reduced cost is 1"
+        );
+    }
+
+    #[rstest]
+    fn routes() {
+        // Routes, which aren't listed, carry nothing
+        let route = |source, destination, amount| Route {
+            source,
+            destination,
+            amount,
+        };
+        let flows = [route(0, 0, 4), route(0, 1, 1), route(1, 1, 5)];
+        let certificate = Certificate::check_routes(&problem().sparse(), &flows, &[0, -2], &[1, 3]);
+        assert_eq!(certificate.violations, vec![]);
+        assert_eq!(certificate.objective, 12);
+
+        let flows = [route(0, 1, 5), route(1, 0, 4), route(1, 1, 1)];
+        let certificate = Certificate::check_routes(&problem().sparse(), &flows, &[0, 0], &[1, 3]);
+        assert_eq!(
+            certificate.violations,
+            Certificate::check(&problem(), &[vec![0, 5], vec![4, 1]], &[0, 0], &[1, 3]).violations
+        );
+        let flows = [route(2, 0, 1)];
+        let certificate = Certificate::check_routes(&problem().sparse(), &flows, &[0, 0], &[1, 3]);
+        assert_eq!(
+            certificate.violations,
+            vec![Violation::RouteOutOfBounds {
+                source: 2,
+                destination: 0
+            }]
+        );
+    }
+
+    #[rstest]
+    fn mismatched_dimensions() {
+        let certificate = Certificate::check(&problem(), &[vec![4, 1], vec![0]], &[0, -2], &[1]);
+        assert_eq!(
+            certificate.violations,
+            vec![
+                Violation::DimensionMismatch {
+                    what: "flow columns".to_string(),
+                    expected: 2,
+                    found: 1
+                },
+                Violation::DimensionMismatch {
+                    what: "destination potentials".to_string(),
+                    expected: 2,
+                    found: 1
+                },
+            ]
+        );
+        assert_eq!(
+            certificate.violations[0].to_string(),
+            "Expected 2 flow columns, found 1"
+        );
+
+        let certificate = Certificate::check(&problem(), &[vec![4, 1]], &[0], &[1, 3]);
+        assert_eq!(certificate.violations.len(), 2);
+        assert!(!certificate.is_valid());
+    }
+
+    #[rstest]
+    fn route_out_of_bounds() {
+        let problem = Problem {
+            forbidden: vec![(2, 0)],
+            capacities: vec![(0, 5, 1)],
+            ..problem()
+        };
+        let certificate =
+            Certificate::check(&problem, &[vec![4, 1], vec![0, 5]], &[0, -2], &[1, 3]);
+        assert_eq!(
+            certificate.violations,
+            vec![
+                Violation::RouteOutOfBounds {
+                    source: 2,
+                    destination: 0
+                },
+                Violation::RouteOutOfBounds {
+                    source: 0,
+                    destination: 5
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    fn forbidden_basis() {
+        // Forbidden routes stay in the basis with zero flow
+        for n in [6, 10, 30].into_iter().cycle().take(30) {
+            let mut problem = GenConfig::default().gen(n);
+            for (i, row) in problem.costs.iter_mut().enumerate() {
+                for (j, cost) in row.iter_mut().enumerate() {
+                    if *cost == M {
+                        problem.forbidden.push((i, j));
+                    }
+                }
+            }
+            let (solver, result) = try_solve::<NorthWest>(problem);
+            let certificate = solver.certificate();
+            match result {
+                Ok(()) => verify(&solver),
+                Err(_) => assert!(certificate
+                    .violations
+                    .iter()
+                    .any(|x| matches!(x, Violation::ForbiddenFlow { .. }))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod solution {
+    use super::*;
+    use crate::solution::*;
+    use rstest::*;
+
+    #[rstest]
+    fn before_solving() {
+        let solver = TransportationSolver::new(GenConfig::default().gen(5)).unwrap();
+        assert!(solver.solution().is_none());
+    }
+
+    #[rstest]
+    #[case(solve_exercise(false))]
+    #[case(solve_wide(false))]
+    #[case(solve_tall(false))]
+    #[case(solve_generated(30, false))]
+    fn consistent(#[case] solver: TransportationSolver) {
+        let problem = problem_of(&solver);
+        let solution = solver.solution().unwrap();
+        let weights = problem.weights();
+
+        assert_eq!(solution.objective, stats(&solver).objective);
+        assert_eq!(
+            solution
+                .routes()
+                .map(|x| x.amount * problem.costs[x.source][x.destination])
+                .sum::<i32>(),
+            solution.objective
+        );
+        for (row, supply) in plan(&solution).iter().zip(problem.supply.iter()) {
+            assert_eq!(row.iter().sum::<i32>(), *supply);
+        }
+        assert_eq!(
+            solution.basis.len(),
+            problem.supply.len() + problem.demand.len() - 1
+        );
+        for (i, j) in solution.basis.iter().copied() {
+            assert!(solution
+                .reduced_costs
+                .iter()
+                .all(|(k, l, _)| (*k, *l) != (i, j)));
+            if !weights[i][j].is_forbidden() {
+                assert_eq!(solution.u[i] + solution.v[j], problem.costs[i][j]);
+            }
+        }
+        for (i, j, reduced) in solution.reduced_costs.iter().copied() {
+            assert!(!weights[i][j].is_forbidden());
+            assert!(reduced >= 0);
+            assert_eq!(reduced, problem.costs[i][j] - solution.u[i] - solution.v[j]);
+        }
+    }
+
+    #[rstest]
+    fn without_dummy() {
+        let solution = solve_shortage(false).solution().unwrap();
+        assert_eq!(plan(&solution), vec![vec![10, 0], vec![5, 5]]);
+        assert_eq!(
+            solution.routes().collect::<Vec<_>>(),
+            vec![
+                Route {
+                    source: 0,
+                    destination: 0,
+                    amount: 10
+                },
+                Route {
+                    source: 1,
+                    destination: 0,
+                    amount: 5
+                },
+                Route {
+                    source: 1,
+                    destination: 1,
+                    amount: 5
+                },
+            ]
+        );
+        assert_eq!((solution.u.len(), solution.v.len()), (2, 2));
+        assert_eq!(solution.stats.unmet_demand, vec![0, 10]);
+    }
+}
+
+#[cfg(test)]
+mod sensitivity {
+    use super::*;
+    use crate::solver::sensitivity::*;
+    use rstest::*;
+
+    fn interval(value: i32, lower: Option<i32>, upper: Option<i32>) -> Interval {
+        Interval {
+            value,
+            lower,
+            upper,
+        }
+    }
+
+    #[rstest]
+    fn before_solving() {
+        let solver = TransportationSolver::new(GenConfig::default().gen(5)).unwrap();
+        assert!(solver.sensitivity().is_none());
+    }
+
+    #[rstest]
+    fn shortage() {
+        let sensitivity = solve_shortage(true).sensitivity().unwrap();
+        assert_eq!(
+            sensitivity.costs,
+            vec![
+                vec![
+                    Some(interval(2, None, Some(6))),
+                    Some(interval(3, Some(-1), None))
+                ],
+                vec![
+                    Some(interval(4, Some(0), Some(6))),
+                    Some(interval(1, Some(-1), Some(5)))
+                ],
+            ]
+        );
+        assert_eq!(
+            sensitivity.supply,
+            vec![
+                interval(10, Some(5), Some(15)),
+                interval(10, Some(5), Some(20))
+            ]
+        );
+        assert_eq!(
+            sensitivity.demand,
+            vec![
+                interval(15, Some(10), Some(20)),
+                interval(15, Some(5), None)
+            ]
+        );
+    }
+
+    /// Surplus leaves from source 1 and shortage is covered at destination 0,
+    /// which have the largest potentials
+    #[rstest]
+    fn balanced() {
+        let problem = Problem {
+            costs: vec![vec![1, 2], vec![3, 1]],
+            supply: vec![10, 20],
+            demand: vec![15, 15],
+            ..Default::default()
+        };
+        let sensitivity = solve_problem(problem.clone(), true).sensitivity().unwrap();
+        assert_eq!(
+            sensitivity.supply,
+            vec![
+                interval(10, Some(0), Some(15)),
+                interval(20, Some(15), None)
+            ]
+        );
+        assert_eq!(
+            sensitivity.demand,
+            vec![
+                interval(15, Some(10), None),
+                interval(15, Some(0), Some(20))
+            ]
+        );
+
+        // Objective at the ends of the supply range follows from the potentials
+        for (supply, objective) in [(0, 30), (15, 30)] {
+            let solver = solve_problem(
+                Problem {
+                    supply: vec![supply, 20],
+                    ..problem.clone()
+                },
+                true,
+            );
+            assert_eq!(stats(&solver).objective, objective);
+        }
+    }
+
+    #[rstest]
+    fn infeasible() {
+        let mut solver = TransportationSolver::new(Problem {
+            costs: vec![vec![1, 2], vec![3, 4]],
+            supply: vec![5, 5],
+            demand: vec![5, 5],
+            forbidden: vec![(0, 0), (1, 0)],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(solver.solve(), Err(SolverError::Infeasible));
+        assert!(solver.sensitivity().is_none());
+    }
+
+    #[rstest]
+    fn forbidden() {
+        let mut solver = TransportationSolver::new(Problem {
+            costs: vec![vec![1, 2], vec![3, 4]],
+            supply: vec![5, 5],
+            demand: vec![5, 5],
+            forbidden: vec![(0, 1)],
+            ..Default::default()
+        })
+        .unwrap();
+        solver.solve().unwrap();
+        let sensitivity = solver.sensitivity().unwrap();
+        assert_eq!(sensitivity.costs[0][1], None);
+        assert!(sensitivity.costs[1][1].is_some());
+    }
+
+    /// Within the ranges the plan stays optimal
+    #[rstest]
+    #[case(solve_exercise(false))]
+    #[case(solve_wide(false))]
+    #[case(solve_tall(false))]
+    #[case(solve_generated(8, false))]
+    fn cost_ranges(#[case] solver: TransportationSolver) {
+        let problem = problem_of(&solver);
+        let shipments = plan(&solver.solution().unwrap());
+        let sensitivity = solver.sensitivity().unwrap();
+
+        for (i, row) in sensitivity.costs.iter().enumerate() {
+            for (j, interval) in row.iter().enumerate() {
+                let Some(interval) = interval else {
+                    assert!(problem.forbidden.contains(&(i, j)));
+                    continue;
+                };
+                assert_eq!(interval.value, problem.costs[i][j]);
+                for cost in [interval.lower, interval.upper].into_iter().flatten() {
+                    assert!(interval.contains(cost));
+                    let mut changed = problem.clone();
+                    changed.costs[i][j] = cost;
+                    let plan = shipments
+                        .iter()
+                        .flatten()
+                        .zip(changed.costs.iter().flatten())
+                        .map(|(amount, cost)| amount * cost)
+                        .sum::<i32>();
+                    let resolved = solve_problem(changed, false);
+                    assert_eq!(stats(&resolved).objective, plan);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod alternative {
+    use super::*;
+    use crate::certificate::Certificate;
+    use rstest::*;
+
+    fn uniform(n: usize) -> TransportationSolver {
+        solve_problem(
+            Problem {
+                costs: vec![vec![1; n]; n],
+                supply: vec![1; n],
+                demand: vec![1; n],
+                ..Default::default()
+            },
+            true,
+        )
+    }
+
+    #[rstest]
+    fn unique() {
+        let solver = solve_problem(
+            Problem {
+                costs: vec![vec![1, 2], vec![2, 1]],
+                supply: vec![5, 5],
+                demand: vec![5, 5],
+                ..Default::default()
+            },
+            true,
+        );
+        assert!(!solver.has_alternative_optima());
+        assert_eq!(solver.alternative_optima(10).len(), 1);
+    }
+
+    #[rstest]
+    fn before_solving() {
+        let solver = TransportationSolver::new(GenConfig::default().gen(5)).unwrap();
+        assert!(!solver.has_alternative_optima());
+        assert!(solver.alternative_optima(10).is_empty());
+    }
+
+    /// Plans of an infeasible problem use forbidden routes, so none of them is optimal
+    #[rstest]
+    fn infeasible() {
+        let mut solver = TransportationSolver::new(Problem {
+            costs: vec![vec![1, 1], vec![1, 1]],
+            supply: vec![5, 5],
+            demand: vec![5, 5],
+            forbidden: vec![(0, 0), (1, 0)],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(solver.solve(), Err(SolverError::Infeasible));
+        assert!(!solver.has_alternative_optima());
+        assert!(solver.alternative_optima(10).is_empty());
+    }
+
+    #[rstest]
+    #[case(2, 10, 2)]
+    #[case(3, 10, 6)]
+    #[case(3, 4, 4)]
+    #[case(3, 1, 1)]
+    #[case(3, 0, 0)]
+    fn permutations(#[case] n: usize, #[case] limit: usize, #[case] expected: usize) {
+        let solver = uniform(n);
+        assert!(solver.has_alternative_optima());
+
+        let optima = solver.alternative_optima(limit);
+        assert_eq!(optima.len(), expected);
+        let problem = problem_of(&solver);
+        for (idx, solution) in optima.iter().enumerate() {
+            assert_eq!(solution.objective, n as i32);
+            let certificate = Certificate::check_routes(
+                &problem.sparse(),
+                &solution.shipments,
+                &solution.u,
+                &solution.v,
+            );
+            assert!(certificate.is_valid());
+            assert!(optima[..idx]
+                .iter()
+                .all(|other| other.shipments != solution.shipments));
+        }
+    }
+
+    #[rstest]
+    fn surplus() {
+        // Any source may keep its goods
+        let solver = solve_penalized(
+            Problem {
+                costs: vec![vec![1], vec![1]],
+                supply: vec![5, 5],
+                demand: vec![5],
+                ..Default::default()
+            },
+            Penalties::default(),
+            true,
+        );
+        let optima = solver.alternative_optima(10);
+        assert_eq!(
+            optima
+                .iter()
+                .map(|x| x.stats.leftover_supply.clone())
+                .collect::<Vec<_>>(),
+            vec![vec![0, 5], vec![5, 0]]
+        );
+    }
+}
+
+#[cfg(test)]
+mod transshipment {
+    use super::*;
+    use crate::transshipment::*;
+    use rstest::*;
+
+    fn arc(from: Node, to: Node, cost: i32) -> Arc {
+        Arc { from, to, cost }
+    }
+
+    #[rstest]
+    fn cross_dock() {
+        use Node::*;
+        let network = Transshipment {
+            supply: vec![10, 10],
+            hubs: 1,
+            demand: vec![8, 12],
+            arcs: vec![
+                arc(Source(0), Hub(0), 1),
+                arc(Source(1), Hub(0), 2),
+                arc(Hub(0), Sink(0), 1),
+                arc(Hub(0), Sink(1), 1),
+                arc(Source(1), Sink(1), 2),
+                // Parallel arc is more expensive
+                arc(Source(1), Sink(1), 5),
+            ],
+        };
+        let solution = network.solve().unwrap();
+        assert_eq!(solution.flows, vec![10, 0, 8, 2, 10, 0]);
+        assert_eq!(solution.objective, 40);
+        assert_eq!(solution.throughput, vec![10]);
+        assert_eq!(solution.unmet_demand, vec![0, 0]);
+        assert_eq!(solution.leftover_supply, vec![0, 0]);
+    }
+
+    #[rstest]
+    fn chain_of_hubs() {
+        use Node::*;
+        let network = Transshipment {
+            supply: vec![7],
+            hubs: 3,
+            demand: vec![7],
+            arcs: vec![
+                arc(Source(0), Hub(0), 1),
+                arc(Hub(0), Hub(1), 1),
+                arc(Hub(1), Hub(2), 1),
+                arc(Hub(2), Sink(0), 1),
+                arc(Hub(0), Sink(0), 5),
+            ],
+        };
+        let solution = network.solve().unwrap();
+        assert_eq!(solution.flows, vec![7, 7, 7, 7, 0]);
+        assert_eq!(solution.throughput, vec![7, 7, 7]);
+        assert_eq!(solution.objective, 28);
+    }
+
+    #[rstest]
+    fn surplus() {
+        use Node::*;
+        let network = Transshipment {
+            supply: vec![10, 10],
+            hubs: 1,
+            demand: vec![5],
+            arcs: vec![
+                arc(Source(0), Hub(0), 1),
+                arc(Source(1), Hub(0), 2),
+                arc(Hub(0), Sink(0), 1),
+            ],
+        };
+        let solution = network.solve().unwrap();
+        assert_eq!(solution.flows, vec![5, 0, 5]);
+        assert_eq!(solution.objective, 10);
+        assert_eq!(solution.throughput, vec![5]);
+        assert_eq!(solution.leftover_supply, vec![5, 10]);
+    }
+
+    #[rstest]
+    fn shortage() {
+        use Node::*;
+        let network = Transshipment {
+            supply: vec![5],
+            hubs: 1,
+            demand: vec![10],
+            arcs: vec![arc(Source(0), Hub(0), 1), arc(Hub(0), Sink(0), 1)],
+        };
+        let solution = network.solve().unwrap();
+        assert_eq!(solution.flows, vec![5, 5]);
+        assert_eq!(solution.throughput, vec![5]);
+        assert_eq!(solution.unmet_demand, vec![5]);
+    }
+
+    #[rstest]
+    fn unreachable() {
+        use Node::*;
+        let network = Transshipment {
+            supply: vec![10],
+            hubs: 1,
+            demand: vec![5, 5],
+            arcs: vec![arc(Source(0), Hub(0), 1), arc(Hub(0), Sink(0), 1)],
+        };
+        assert_eq!(network.solve().unwrap_err(), SolverError::Infeasible);
+    }
+
+    #[rstest]
+    #[case(arc(Node::Sink(0), Node::Hub(0), 1))]
+    #[case(arc(Node::Hub(0), Node::Source(0), 1))]
+    #[case(arc(Node::Hub(0), Node::Hub(0), 1))]
+    #[case(arc(Node::Source(0), Node::Hub(1), 1))]
+    #[case(arc(Node::Source(1), Node::Sink(0), 1))]
+    fn invalid_arc(#[case] invalid: Arc) {
+        let network = Transshipment {
+            supply: vec![5],
+            hubs: 1,
+            demand: vec![5],
+            arcs: vec![arc(Node::Source(0), Node::Sink(0), 1), invalid],
+        };
+        assert_eq!(
+            network.solve().unwrap_err(),
+            SolverError::InvalidArc { arc: 1 }
+        );
+    }
+}
+
+#[cfg(test)]
+mod capacity {
+    use super::*;
+    use crate::solver::pricing::*;
+    use rand::prelude::*;
+    use rstest::*;
+
+    /// Equivalent problem without capacities: every capacitated route is split by
+    /// an extra destination and source, which can't pass on more than the capacity
+    fn split(problem: &Problem) -> Problem {
+        let (m, n, k) = (
+            problem.supply.len(),
+            problem.demand.len(),
+            problem.capacities.len(),
+        );
+        let mut split = Problem {
+            costs: vec![vec![0; n + k]; m + k],
+            supply: problem.supply.clone(),
+            demand: problem.demand.clone(),
+            ..Default::default()
+        };
+        for i in 0..m {
+            split.costs[i][..n].copy_from_slice(&problem.costs[i]);
+        }
+        for (t, (i, j, capacity)) in problem.capacities.iter().copied().enumerate() {
+            split.supply.push(capacity);
+            split.demand.push(capacity);
+            split.costs[i][n + t] = problem.costs[i][j];
+            split.forbidden.push((i, j));
+            for x in 0..m + k {
+                if x != i && x != m + t {
+                    split.forbidden.push((x, n + t));
+                }
+            }
+            for y in 0..n + k {
+                if y != j && y != n + t {
+                    split.forbidden.push((m + t, y));
+                }
+            }
+        }
+        split
+    }
+
+    #[rstest]
+    fn binding() {
+        let solver = solve_problem(
+            Problem {
+                costs: vec![vec![1, 2], vec![2, 1]],
+                supply: vec![10, 10],
+                demand: vec![10, 10],
+                capacities: vec![(0, 0, 4)],
+                ..Default::default()
+            },
+            true,
+        );
+        let solution = solver.solution().unwrap();
+        assert_eq!(plan(&solution), vec![vec![4, 6], vec![6, 4]]);
+        assert_eq!(solution.objective, 32);
+        assert_eq!(solution.stats.initial, "artificial");
+    }
+
+    #[rstest]
+    fn saturated() {
+        // Cheap route is used up to its capacity, so its reduced cost is negative
+        let solver = solve_problem(
+            Problem {
+                costs: vec![vec![1, 5], vec![5, 5]],
+                supply: vec![10, 10],
+                demand: vec![10, 10],
+                capacities: vec![(0, 0, 4), (1, 1, 6)],
+                ..Default::default()
+            },
+            true,
+        );
+        let solution = solver.solution().unwrap();
+        assert_eq!(plan(&solution), vec![vec![4, 6], vec![6, 4]]);
+        assert!(solution
+            .reduced_costs
+            .iter()
+            .any(|(i, j, x)| (*i, *j) == (0, 0) && *x < 0));
+    }
+
+    #[rstest]
+    fn loose() {
+        let solver = solve_problem(
+            Problem {
+                costs: vec![vec![1, 2], vec![2, 1]],
+                supply: vec![10, 10],
+                demand: vec![10, 10],
+                capacities: vec![(0, 0, 15), (1, 1, 10)],
+                ..Default::default()
+            },
+            true,
+        );
+        assert_eq!(stats(&solver).initial, NorthWest::NAME);
+        assert_eq!(stats(&solver).objective, 20);
+    }
+
+    /// Forbidden route stays in the optimal basis, while an allowed one is used
+    /// up to its capacity, so its reduced cost must not be positive
+    #[rstest]
+    #[case(Problem {
+        costs: vec![vec![-2, 0, 1]],
+        supply: vec![8],
+        demand: vec![9, 6, 8],
+        forbidden: vec![(0, 0)],
+        capacities: vec![(0, 1, 0), (0, 2, 8)],
+        ..Default::default()
+    })]
+    #[case(Problem {
+        costs: vec![vec![8, 6], vec![-2, 8], vec![6, -2]],
+        supply: vec![6, 7, 0],
+        demand: vec![4, 1],
+        forbidden: vec![(0, 1), (2, 1)],
+        capacities: vec![(0, 1, 2), (1, 0, 1), (1, 1, 1), (2, 1, 0)],
+        ..Default::default()
+    })]
+    fn saturated_with_forbidden_basis(
+        #[case] problem: Problem,
+        #[values(
+            TransportationSolver::solve_using::<NorthWest, Bland>,
+            TransportationSolver::solve_using::<Russell, Bland>,
+            TransportationSolver::solve_using::<Vogel, Dantzig>,
+        )]
+        solve: fn(&mut TransportationSolver) -> Result<(), SolverError>,
+    ) {
+        let mut solver = TransportationSolver::new(problem).expect("Invalid problem");
+        solve(&mut solver).expect("Failed to solve");
+        verify(&solver);
+    }
+
+    #[rstest]
+    fn smallest_applies() {
+        let solver = solve_problem(
+            Problem {
+                costs: vec![vec![1, 2], vec![2, 1]],
+                supply: vec![10, 10],
+                demand: vec![10, 10],
+                capacities: vec![(0, 0, 8), (0, 0, 4)],
+                ..Default::default()
+            },
+            true,
+        );
+        assert_eq!(stats(&solver).objective, 32);
+    }
+
+    #[rstest]
+    fn infeasible() {
+        let (solver, result) = try_solve::<NorthWest>(Problem {
+            costs: vec![vec![1, 2]],
+            supply: vec![10],
+            demand: vec![5, 5],
+            capacities: vec![(0, 0, 4)],
+            ..Default::default()
+        });
+        assert_eq!(result, Err(SolverError::Infeasible));
+        assert!(!stats(&solver).feasible);
+    }
+
+    #[rstest]
+    fn with_dummy() {
+        let solver = solve_penalized(
+            Problem {
+                costs: vec![vec![1, 2], vec![2, 1]],
+                supply: vec![10, 10],
+                demand: vec![5, 5],
+                capacities: vec![(0, 0, 2), (1, 1, 3)],
+                ..Default::default()
+            },
+            Penalties::default(),
+            true,
+        );
+        let solution = solver.solution().unwrap();
+        assert_eq!(plan(&solution), vec![vec![2, 2], vec![3, 3]]);
+        assert_eq!(solution.stats.leftover_supply, vec![6, 4]);
+    }
+
+    #[rstest]
+    fn alternative() {
+        let solver = solve_problem(
+            Problem {
+                costs: vec![vec![1, 1], vec![1, 1]],
+                supply: vec![5, 5],
+                demand: vec![5, 5],
+                capacities: vec![(0, 0, 3)],
+                ..Default::default()
+            },
+            true,
+        );
+        let mut plans = solver
+            .alternative_optima(10)
+            .into_iter()
+            .map(|x| plan(&x))
+            .collect::<Vec<_>>();
+        plans.sort();
+        assert_eq!(
+            plans,
+            vec![vec![vec![0, 5], vec![5, 0]], vec![vec![3, 2], vec![2, 3]]]
+        );
+    }
+
+    #[rstest]
+    fn exceeded() {
+        let problem = Problem {
+            costs: vec![vec![1]],
+            supply: vec![5],
+            demand: vec![5],
+            capacities: vec![(0, 0, 4)],
+            ..Default::default()
+        };
+        let certificate = Certificate::check(&problem, &[vec![5]], &[0], &[1]);
+        assert_eq!(
+            certificate.violations,
+            vec![Violation::CapacityExceeded {
+                source: 0,
+                destination: 0,
+                flow: 5,
+                capacity: 4
+            }]
+        );
+    }
+
+    #[rstest]
+    #[case(NorthWest)]
+    #[case(LeastCost)]
+    #[case(Vogel)]
+    #[case(Russell)]
+    fn random<I: InitialSolution>(#[case] _method: I) {
+        let mut rng = StdRng::seed_from_u64(13);
+        for _ in 0..30 {
+            let (m, n) = (rng.gen_range(1..=6), rng.gen_range(1..=6));
+            let problem = random_problem(&mut rng, (m, n), 0.0, 0.4);
+            let (solver, result) = try_solve::<I>(problem.clone());
+            let (expected, expected_result) = try_solve::<I>(split(&problem));
+            assert_eq!(result, expected_result);
+            if result.is_ok() {
+                verify(&solver);
+                assert_eq!(stats(&solver).objective, stats(&expected).objective);
+            }
+        }
+    }
+
+    /// Route without capacity has a negative reduced cost, which doesn't limit
+    /// the ranges of other costs
+    #[rstest]
+    fn sensitivity_with_zero_capacity() {
+        let problem = Problem {
+            costs: vec![
+                vec![5, 1, 2, 4],
+                vec![1, 2, 7, 6],
+                vec![7, 8, 10, 8],
+                vec![3, 2, 8, 6],
+            ],
+            supply: vec![13, 8, 0, 13],
+            demand: vec![16, 18, 8, 19],
+            capacities: vec![
+                (0, 0, 7),
+                (0, 1, 0),
+                (0, 2, 2),
+                (1, 0, 10),
+                (1, 2, 7),
+                (2, 0, 6),
+                (2, 2, 10),
+                (3, 1, 1),
+                (3, 2, 9),
+            ],
+            ..Default::default()
+        };
+        let solver = solve_problem(problem.clone(), true);
+        let shipments = plan(&solver.solution().unwrap());
+        let sensitivity = solver.sensitivity().unwrap();
+        assert_eq!(sensitivity.costs[0][1].unwrap().lower, None);
+        for (i, row) in sensitivity.costs.iter().enumerate() {
+            for (j, interval) in row.iter().enumerate() {
+                let interval = interval.unwrap();
+                if let (Some(lower), Some(upper)) = (interval.lower, interval.upper) {
+                    assert!(lower <= upper, "Empty range of ({i}, {j})");
+                }
+                for cost in [interval.lower, interval.upper].into_iter().flatten() {
+                    let mut changed = problem.clone();
+                    changed.costs[i][j] = cost;
+                    let plan = shipments
+                        .iter()
+                        .flatten()
+                        .zip(changed.costs.iter().flatten())
+                        .map(|(amount, cost)| amount * cost)
+                        .sum::<i32>();
+                    assert_eq!(stats(&solve_problem(changed, true)).objective, plan);
+                }
+            }
+        }
+    }
+
+    /// Within the ranges the plan stays optimal
+    #[rstest]
+    fn sensitivity() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let problem = (0..)
+            .map(|_| random_problem(&mut rng, (4, 4), 0.0, 0.4))
+            .find(|problem| try_solve::<NorthWest>(problem.clone()).1.is_ok())
+            .unwrap();
+        let solver = solve_problem(problem.clone(), true);
+        let shipments = plan(&solver.solution().unwrap());
+        let sensitivity = solver.sensitivity().unwrap();
+
+        for (i, row) in sensitivity.costs.iter().enumerate() {
+            for (j, interval) in row.iter().enumerate() {
+                for cost in [interval.unwrap().lower, interval.unwrap().upper]
+                    .into_iter()
+                    .flatten()
+                {
+                    let mut changed = problem.clone();
+                    changed.costs[i][j] = cost;
+                    let plan = shipments
+                        .iter()
+                        .flatten()
+                        .zip(changed.costs.iter().flatten())
+                        .map(|(amount, cost)| amount * cost)
+                        .sum::<i32>();
+                    assert_eq!(stats(&solve_problem(changed, true)).objective, plan);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod assignment {
+    use super::*;
+    use crate::assignment::*;
+    use rand::prelude::*;
+    use rstest::*;
+
+    /// Cheapest assignment of every row from `i` on, found by trying all of them
+    fn brute_force(
+        costs: &[Vec<i32>],
+        forbidden: &[(usize, usize)],
+        i: usize,
+        used: &mut Vec<bool>,
+    ) -> Option<i32> {
+        if i == costs.len() {
+            return Some(0);
+        }
+        let mut best = None;
+        for j in 0..used.len() {
+            if used[j] || forbidden.contains(&(i, j)) {
+                continue;
+            }
+            used[j] = true;
+            if let Some(cost) = brute_force(costs, forbidden, i + 1, used) {
+                let cost = cost + costs[i][j];
+                best = Some(best.map_or(cost, |x: i32| x.min(cost)));
+            }
+            used[j] = false;
+        }
+        best
+    }
+
+    /// Checks the labels and returns the objective
+    fn check(problem: &Assignment, solution: &AssignmentSolution) -> i32 {
+        let (rows, cols) = (problem.costs.len(), problem.costs[0].len());
+        assert_eq!(solution.assignment.len(), rows);
+        assert_eq!(solution.assignment.iter().flatten().count(), rows.min(cols));
+        let mut used = vec![false; cols];
+        for j in solution.assignment.iter().flatten() {
+            assert!(!used[*j]);
+            used[*j] = true;
+        }
+
+        for (i, row) in problem.costs.iter().enumerate() {
+            for (j, cost) in row.iter().enumerate() {
+                if !problem.forbidden.contains(&(i, j)) {
+                    assert!(solution.u[i] + solution.v[j] <= *cost);
+                }
+            }
+            if let Some(j) = solution.assignment[i] {
+                assert!(!problem.forbidden.contains(&(i, j)));
+                assert_eq!(solution.u[i] + solution.v[j], row[j]);
+            }
+        }
+        assert_eq!(
+            solution.u.iter().chain(solution.v.iter()).sum::<i32>(),
+            solution.objective
+        );
+        solution
+            .assignment
+            .iter()
+            .enumerate()
+            .filter_map(|(i, j)| j.map(|j| problem.costs[i][j]))
+            .sum()
+    }
+
+    #[rstest]
+    fn square() {
+        let problem = Assignment {
+            costs: vec![vec![4, 1, 3], vec![2, 0, 5], vec![3, 2, 2]],
+            ..Default::default()
+        };
+        let solution = problem.solve().unwrap();
+        assert_eq!(solution.assignment, vec![Some(1), Some(0), Some(2)]);
+        assert_eq!(solution.objective, 5);
+        check(&problem, &solution);
+    }
+
+    #[rstest]
+    fn more_rows() {
+        let problem = Assignment {
+            costs: vec![vec![4, 1], vec![2, 0], vec![1, 5]],
+            ..Default::default()
+        };
+        let solution = problem.solve().unwrap();
+        assert_eq!(solution.assignment, vec![None, Some(1), Some(0)]);
+        assert_eq!(check(&problem, &solution), 1);
+    }
+
+    #[rstest]
+    fn forbidden() {
+        let problem = Assignment {
+            costs: vec![vec![1, 5], vec![1, 5]],
+            forbidden: vec![(0, 1)],
+        };
+        let solution = problem.solve().unwrap();
+        assert_eq!(solution.assignment, vec![Some(0), Some(1)]);
+        assert_eq!(check(&problem, &solution), 6);
+    }
+
+    #[rstest]
+    fn infeasible() {
+        let problem = Assignment {
+            costs: vec![vec![1, 5], vec![1, 5]],
+            forbidden: vec![(0, 1), (1, 1)],
+        };
+        assert_eq!(problem.solve().unwrap_err(), SolverError::Infeasible);
+    }
+
+    #[rstest]
+    fn invalid() {
+        let empty = Assignment::<i32>::default();
+        assert_eq!(empty.solve().unwrap_err(), SolverError::Empty);
+        let ragged = Assignment {
+            costs: vec![vec![1, 2], vec![3]],
+            ..Default::default()
+        };
+        assert_eq!(
+            ragged.solve().unwrap_err(),
+            SolverError::DimensionMismatch {
+                what: "cost columns".to_string(),
+                expected: 2,
+                found: 1
+            }
+        );
+        let out_of_bounds = Assignment {
+            costs: vec![vec![1, 2]],
+            forbidden: vec![(1, 0)],
+        };
+        assert_eq!(
+            out_of_bounds.solve().unwrap_err(),
+            SolverError::RouteOutOfBounds {
+                source: 1,
+                destination: 0
+            }
+        );
+    }
+
+    #[rstest]
+    fn float() {
+        let problem = Assignment {
+            costs: vec![vec![0.5, 1.25], vec![0.75, 2.0]],
+            ..Default::default()
+        };
+        let solution = problem.solve().unwrap();
+        assert_eq!(solution.assignment, vec![Some(1), Some(0)]);
+        assert!(solution.objective.compare(&2.0).is_eq());
+    }
+
+    #[rstest]
+    fn random() {
+        let mut rng = StdRng::seed_from_u64(14);
+        for _ in 0..200 {
+            let (rows, cols) = (rng.gen_range(1..=6), rng.gen_range(1..=6));
+            let Problem {
+                costs, forbidden, ..
+            } = random_problem(&mut rng, (rows, cols), 0.2, 0.0);
+            let problem = Assignment { costs, forbidden };
+
+            // Brute force assigns every row, so it is applied to the shorter side
+            let expected = match rows <= cols {
+                true => brute_force(
+                    &problem.costs,
+                    &problem.forbidden,
+                    0,
+                    &mut vec![false; cols],
+                ),
+                false => {
+                    let costs = (0..cols)
+                        .map(|j| problem.costs.iter().map(|row| row[j]).collect())
+                        .collect::<Vec<_>>();
+                    let forbidden = problem
+                        .forbidden
+                        .iter()
+                        .map(|(i, j)| (*j, *i))
+                        .collect::<Vec<_>>();
+                    brute_force(&costs, &forbidden, 0, &mut vec![false; rows])
+                }
+            };
+            match problem.solve() {
+                Ok(solution) => assert_eq!(Some(check(&problem, &solution)), expected),
+                Err(error) => {
+                    assert_eq!(error, SolverError::Infeasible);
+                    assert_eq!(expected, None);
+                }
+            }
+        }
+    }
+
+    /// Same optimum as the transportation problem with unit supply and demand
+    #[rstest]
+    #[case(20)]
+    #[case(40)]
+    fn transportation(#[case] n: usize) {
+        let mut rng = StdRng::seed_from_u64(n as u64);
+        let problem = Assignment {
+            costs: random_problem(&mut rng, (n, n), 0.0, 0.0).costs,
+            ..Default::default()
+        };
+        let solution = problem.solve().unwrap();
+        let solver = solve_problem(
+            Problem {
+                costs: problem.costs.clone(),
+                supply: vec![1; n],
+                demand: vec![1; n],
+                ..Default::default()
+            },
+            true,
+        );
+        assert_eq!(check(&problem, &solution), stats(&solver).objective);
+    }
+
+    #[rstest]
+    fn large() {
+        let mut rng = StdRng::seed_from_u64(100);
+        let Problem {
+            costs, forbidden, ..
+        } = random_problem(&mut rng, (100, 150), 0.2, 0.0);
+        let problem = Assignment { costs, forbidden };
+        let solution = problem.solve().unwrap();
+        check(&problem, &solution);
+    }
+}
+
+#[cfg(test)]
+mod warm {
+    use super::*;
+    use crate::solver::warm::*;
+    use rand::prelude::*;
+    use rstest::*;
+
+    fn change(rng: &mut StdRng, problem: &mut Problem) -> Change {
+        let (m, n) = (problem.supply.len(), problem.demand.len());
+        let (source, destination) = (rng.gen_range(0..m), rng.gen_range(0..n));
+        let change = match rng.gen_range(0..3) {
+            0 => Change::Cost {
+                source,
+                destination,
+                value: rng.gen_range(1..=10),
+            },
+            1 => Change::Supply {
+                source,
+                value: rng.gen_range(0..=20),
+            },
+            _ => Change::Demand {
+                destination,
+                value: rng.gen_range(0..=20),
+            },
+        };
+        match change {
+            Change::Cost { value, .. } => problem.costs[source][destination] = value,
+            Change::Supply { value, .. } => problem.supply[source] = value,
+            Change::Demand { value, .. } => problem.demand[destination] = value,
+        }
+        change
+    }
+
+    fn problem() -> Problem {
+        Problem {
+            costs: vec![vec![1, 2], vec![2, 1]],
+            supply: vec![10, 10],
+            demand: vec![5, 5],
+            ..Default::default()
+        }
+    }
+
+    /// Re-optimized plans are as good as the ones solved from scratch
+    #[rstest]
+    #[case(false)]
+    #[case(true)]
+    fn random(#[case] capacities: bool) {
+        let mut rng = StdRng::seed_from_u64(16);
+        for _ in 0..30 {
+            let (m, n) = (rng.gen_range(1..=6), rng.gen_range(1..=6));
+            let mut problem =
+                random_problem(&mut rng, (m, n), 0.1, if capacities { 0.3 } else { 0.0 });
+            let mut solver = TransportationSolver::new(problem.clone()).unwrap();
+            let _ = solver.solve();
+            for _ in 0..5 {
+                let changes = (0..rng.gen_range(1..=3))
+                    .map(|_| change(&mut rng, &mut problem))
+                    .collect::<Vec<_>>();
+                let result = solver.resolve(&changes);
+                let (expected, expected_result) = try_solve::<NorthWest>(problem.clone());
+                assert_eq!(result, expected_result);
+                match result {
+                    Ok(()) => {
+                        verify(&solver);
+                        assert_eq!(stats(&solver).objective, stats(&expected).objective);
+                    }
+                    // Failed change may be rolled back, so the next one starts
+                    // from the changed problem solved from scratch
+                    Err(_) => solver = expected,
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    fn unchanged() {
+        let mut solver = solve_problem(problem(), true);
+        let iterations = stats(&solver).iterations;
+        solver.resolve(&[]).unwrap();
+        let stats = stats(&solver);
+        assert_eq!(stats.initial, "warm");
+        assert_eq!(stats.iterations, 1);
+        assert_eq!(stats.warm_start.as_ref().unwrap().dual_pivots, 0);
+        assert_eq!(stats.saved_iterations(), Some(iterations as isize - 1));
+    }
+
+    #[rstest]
+    fn cost() {
+        let mut solver = solve_problem(problem(), true);
+        solver
+            .resolve(&[Change::Cost {
+                source: 0,
+                destination: 0,
+                value: 3,
+            }])
+            .unwrap();
+        verify(&solver);
+        let solution = solver.solution().unwrap();
+        assert_eq!(plan(&solution), vec![vec![0, 0], vec![5, 5]]);
+        assert_eq!(solution.objective, 15);
+        assert_eq!(solution.stats.warm_start.unwrap().dual_pivots, 0);
+    }
+
+    #[rstest]
+    fn demand() {
+        // Second source must take over the demand it couldn't cover before
+        let mut solver = solve_problem(problem(), true);
+        solver
+            .resolve(&[
+                Change::Supply {
+                    source: 1,
+                    value: 2,
+                },
+                Change::Demand {
+                    destination: 1,
+                    value: 7,
+                },
+            ])
+            .unwrap();
+        verify(&solver);
+        let solution = solver.solution().unwrap();
+        assert_eq!(plan(&solution), vec![vec![5, 5], vec![0, 2]]);
+        assert_eq!(solution.stats.leftover_supply, vec![0, 0]);
+        assert!(solution.stats.warm_start.unwrap().dual_pivots > 0);
+    }
+
+    #[rstest]
+    fn dummy_side() {
+        // Demand exceeds supply, so the dummy destination can't take the difference
+        let mut solver = solve_problem(problem(), true);
+        solver
+            .resolve(&[Change::Demand {
+                destination: 0,
+                value: 20,
+            }])
+            .unwrap();
+        verify(&solver);
+        assert!(stats(&solver).warm_start.is_none());
+        assert_eq!(stats(&solver).unmet_demand, vec![5, 0]);
+    }
+
+    #[rstest]
+    fn before_solving() {
+        let mut solver = TransportationSolver::new(problem()).unwrap();
+        solver.resolve(&[]).unwrap();
+        verify(&solver);
+        assert_eq!(stats(&solver).initial, NorthWest::NAME);
+    }
+
+    #[rstest]
+    fn capacity() {
+        let mut solver = solve_problem(
+            Problem {
+                capacities: vec![(0, 0, 6)],
+                ..problem()
+            },
+            true,
+        );
+        solver
+            .resolve(&[Change::Demand {
+                destination: 0,
+                value: 12,
+            }])
+            .unwrap();
+        verify(&solver);
+        let solution = solver.solution().unwrap();
+        assert_eq!(plan(&solution), vec![vec![6, 1], vec![6, 4]]);
+    }
+
+    #[rstest]
+    fn infeasible() {
+        // Capacity can't carry the new demand, so the dual simplex fails
+        let mut solver = solve_problem(
+            Problem {
+                costs: vec![vec![1, 2]],
+                supply: vec![10],
+                demand: vec![5, 5],
+                capacities: vec![(0, 0, 5)],
+                ..Default::default()
+            },
+            true,
+        );
+        let result = solver.resolve(&[
+            Change::Demand {
+                destination: 0,
+                value: 6,
+            },
+            Change::Demand {
+                destination: 1,
+                value: 4,
+            },
+        ]);
+        assert_eq!(result, Err(SolverError::Infeasible));
+
+        // Previous plan is kept along with the problem it solves
+        let solution = solver.solution().unwrap();
+        assert_eq!(plan(&solution), vec![vec![5, 5]]);
+        assert!(solution.stats.warm_start.is_none());
+        assert_eq!(problem_of(&solver).demand, vec![5, 5]);
+        verify(&solver);
+    }
+
+    #[rstest]
+    #[case(Change::Cost { source: 0, destination: 2, value: 1 })]
+    #[case(Change::Supply { source: 2, value: 1 })]
+    #[case(Change::Demand { destination: 2, value: 1 })]
+    fn out_of_bounds(#[case] change: Change) {
+        let mut solver = solve_problem(problem(), true);
+        let unchanged = Change::Supply {
+            source: 0,
+            value: 10,
+        };
+        assert_eq!(
+            solver.resolve(&[unchanged, change]),
+            Err(SolverError::ChangeOutOfBounds { change: 1 })
+        );
+        assert!(solver.solution().is_some());
+    }
+
+    #[rstest]
+    fn negative() {
+        let mut solver = solve_problem(problem(), true);
+        assert_eq!(
+            solver.resolve(&[Change::Demand {
+                destination: 1,
+                value: -1,
+            }]),
+            Err(SolverError::NegativeDemand {
+                destination: 1,
+                value: -1
+            })
+        );
+    }
+
+    /// Small change of a large problem needs few pivots
+    #[rstest]
+    fn saved() {
+        let mut rng = StdRng::seed_from_u64(60);
+        let mut problem = random_problem(&mut rng, (60, 60), 0.1, 0.0);
+        let mut solver = solve_problem(problem.clone(), true);
+        let changes = [change(&mut rng, &mut problem)];
+        solver.resolve(&changes).unwrap();
+        verify(&solver);
+        assert_eq!(
+            stats(&solver).objective,
+            stats(&solve_problem(problem, true)).objective
+        );
+        assert!(stats(&solver).saved_iterations().unwrap() > 0);
+    }
+}
+
+#[cfg(all(test, feature = "multi-commodity"))]
+mod commodity {
+    use super::*;
+    use crate::commodity::*;
+    use rand::prelude::*;
+    use rstest::*;
+
+    fn commodity(costs: Vec<Vec<f64>>, supply: Vec<f64>, demand: Vec<f64>) -> Problem<f64> {
+        Problem {
+            costs,
+            supply,
+            demand,
+            ..Default::default()
+        }
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{a} != {b}");
+    }
+
+    /// Without shared capacities commodities are independent transportation problems
+    #[rstest]
+    fn independent() {
+        let mut rng = StdRng::seed_from_u64(17);
+        for _ in 0..20 {
+            let (m, n) = (rng.gen_range(1..=5), rng.gen_range(1..=5));
+            let commodities = (0..3)
+                .map(|_| random_problem(&mut rng, (m, n), 0.1, 0.2).convert())
+                .collect::<Vec<_>>();
+            let expected = commodities
+                .iter()
+                .map(|problem| {
+                    let mut solver = TransportationSolver::new(problem.clone()).unwrap();
+                    solver.solve().ok().map(|_| stats(&solver).objective)
+                })
+                .collect::<Option<Vec<_>>>();
+
+            let model = MultiCommodity {
+                commodities,
+                ..Default::default()
+            };
+            match (model.solve(), expected) {
+                (Ok(solution), Some(expected)) => {
+                    for (cost, expected) in solution.costs.iter().zip(expected.iter()) {
+                        assert_close(*cost, *expected);
+                    }
+                    assert_close(solution.objective, expected.iter().sum());
+                }
+                (Err(error), None) => assert_eq!(error, SolverError::Infeasible),
+                (result, expected) => panic!("{result:?} != {expected:?}"),
+            }
+        }
+    }
+
+    #[rstest]
+    fn shared() {
+        // Both commodities prefer the first route, which carries only 10 units.
+        // Moving the second one away costs less
+        let model = MultiCommodity {
+            commodities: vec![
+                commodity(
+                    vec![vec![1.0, 5.0], vec![5.0, 5.0]],
+                    vec![8.0, 8.0],
+                    vec![8.0, 8.0],
+                ),
+                commodity(
+                    vec![vec![1.0, 2.0], vec![2.0, 2.0]],
+                    vec![8.0, 8.0],
+                    vec![8.0, 8.0],
+                ),
+            ],
+            capacities: vec![(0, 0, 10.0)],
+            ..Default::default()
+        };
+        let solution = model.solve().unwrap();
+        assert_eq!(solution.flows[0], vec![vec![8.0, 0.0], vec![0.0, 8.0]]);
+        assert_eq!(solution.flows[1], vec![vec![2.0, 6.0], vec![6.0, 2.0]]);
+        assert_close(solution.objective, 48.0 + 30.0);
+        assert_close(solution.costs[0], 48.0);
+    }
+
+    #[rstest]
+    fn unbalanced() {
+        let model = MultiCommodity {
+            commodities: vec![
+                commodity(vec![vec![1.0], vec![2.0]], vec![5.0, 5.0], vec![6.0]),
+                commodity(vec![vec![1.0], vec![2.0]], vec![1.0, 2.0], vec![6.0]),
+            ],
+            ..Default::default()
+        };
+        let solution = model.solve().unwrap();
+        assert_eq!(
+            solution.leftover_supply,
+            vec![vec![0.0, 4.0], vec![0.0, 0.0]]
+        );
+        assert_eq!(solution.unmet_demand, vec![vec![0.0], vec![3.0]]);
+    }
+
+    #[rstest]
+    fn integral() {
+        let mut rng = StdRng::seed_from_u64(18);
+        for _ in 0..10 {
+            let (m, n) = (rng.gen_range(1..=4), rng.gen_range(1..=4));
+            let mut model = MultiCommodity {
+                commodities: (0..2)
+                    .map(|_| random_problem(&mut rng, (m, n), 0.1, 0.2).convert())
+                    .collect(),
+                capacities: (0..m)
+                    .flat_map(|i| (0..n).map(move |j| (i, j)))
+                    .map(|(i, j)| (i, j, rng.gen_range(5..=15) as f64))
+                    .collect(),
+                ..Default::default()
+            };
+            let Ok(relaxed) = model.solve() else {
+                continue;
+            };
+            model.integral = true;
+            if let Ok(solution) = model.solve() {
+                assert!(solution
+                    .flows
+                    .iter()
+                    .flatten()
+                    .flatten()
+                    .all(|x| (x - x.round()).abs() < 1e-6));
+                assert!(solution.objective >= relaxed.objective - 1e-6);
+            }
+        }
+    }
+
+    #[rstest]
+    fn infeasible() {
+        let model = MultiCommodity {
+            commodities: vec![
+                commodity(vec![vec![1.0]], vec![5.0], vec![5.0]),
+                commodity(vec![vec![1.0]], vec![5.0], vec![5.0]),
+            ],
+            capacities: vec![(0, 0, 8.0)],
+            ..Default::default()
+        };
+        assert_eq!(model.solve().unwrap_err(), SolverError::Infeasible);
+    }
+
+    #[rstest]
+    #[case(vec![], vec![], SolverError::Empty)]
+    #[case(
+        vec![commodity(vec![vec![1.0]], vec![1.0], vec![1.0]), commodity(vec![vec![1.0, 1.0]], vec![1.0], vec![1.0, 0.0])],
+        vec![],
+        SolverError::DimensionMismatch { what: "destinations".to_string(), expected: 1, found: 2 }
+    )]
+    #[case(
+        vec![commodity(vec![vec![1.0]], vec![1.0], vec![1.0])],
+        vec![(0, 1, 1.0)],
+        SolverError::RouteOutOfBounds { source: 0, destination: 1 }
+    )]
+    #[case(
+        vec![commodity(vec![vec![1.0]], vec![1.0], vec![1.0])],
+        vec![(0, 0, -1.0)],
+        SolverError::NegativeCapacity { source: 0, destination: 0, value: -1.0 }
+    )]
+    fn validation(
+        #[case] commodities: Vec<Problem<f64>>,
+        #[case] capacities: Vec<(usize, usize, f64)>,
+        #[case] expected: SolverError<f64>,
+    ) {
+        let model = MultiCommodity {
+            commodities,
+            capacities,
+            ..Default::default()
+        };
+        assert_eq!(model.validate(), Err(expected));
+    }
+}
+
+#[cfg(test)]
+mod io {
+    use super::*;
+    use crate::io::*;
+    use rstest::*;
+
+    const MATRIX: &str = ",Berlin,Paris,Supply\n\
+                          Hamburg,4,8,76\n\
+                          Lyon,M,3,82\n\
+                          Demand,72,86\n";
+
+    #[rstest]
+    fn matrix() {
+        let problem = read_matrix::<i32>(MATRIX).unwrap();
+        assert_eq!(problem.costs, vec![vec![4, 8], vec![0, 3]]);
+        assert_eq!(problem.supply, vec![76, 82]);
+        assert_eq!(problem.demand, vec![72, 86]);
+        assert_eq!(problem.forbidden, vec![(1, 0)]);
+        assert_eq!(problem.sources, vec!["Hamburg", "Lyon"]);
+        assert_eq!(problem.destinations, vec!["Berlin", "Paris"]);
+        assert_eq!(write_matrix(&problem), MATRIX);
+    }
+
+    /// Forbidden cells may be left empty or dashed, and the total supply
+    /// may follow the demands
+    #[rstest]
+    fn matrix_variants() {
+        let problem = read_matrix::<f64>(
+            " , A , B , Supply\n\
+             \n\
+             X, 1.5, -, 2\n\
+             Y, , 2, 3\n\
+             Demand, 2, 3, 5\n",
+        )
+        .unwrap();
+        assert_eq!(problem.costs, vec![vec![1.5, 0.0], vec![0.0, 2.0]]);
+        assert_eq!(problem.forbidden, vec![(0, 1), (1, 0)]);
+        assert_eq!(problem.demand, vec![2.0, 3.0]);
+    }
+
+    #[rstest]
+    fn plan() {
+        let mut solver = TransportationSolver::new(read_matrix::<i32>(MATRIX).unwrap()).unwrap();
+        solver.solve().unwrap();
+        assert_eq!(
+            write_plan(&solver.solution().unwrap()),
+            "from,to,amount\n\
+             Hamburg,Berlin,72\n\
+             Hamburg,Paris,4\n\
+             Lyon,Paris,82\n"
+        );
+    }
+
+    #[rstest]
+    fn unnamed() {
+        let solution = solve_shortage(false).solution().unwrap();
+        assert_eq!(
+            write_plan(&solution),
+            "from,to,amount\nS1,D1,10\nS2,D1,5\nS2,D2,5\n"
+        );
+    }
+
+    #[rstest]
+    fn edges() {
+        let problem = read_edges::<i32>(
+            "from,to,cost,capacity\n\
+             Hamburg,Berlin,4,\n\
+             Lyon,Paris,3,50\n\
+             Hamburg,Paris,8\n",
+            "name,supply\nHamburg,76\nLyon,82\n",
+            "name,demand\nBerlin,72\nParis,86\n",
+        )
+        .unwrap();
+        assert_eq!(problem.costs, vec![vec![4, 8], vec![0, 3]]);
+        assert_eq!(problem.forbidden, vec![(1, 0)]);
+        assert_eq!(problem.capacities, vec![(1, 1, 50)]);
+        assert_eq!(write_matrix(&problem), MATRIX);
+        assert_eq!(
+            write_edges(&problem),
+            "from,to,cost,capacity\n\
+             Hamburg,Berlin,4,\n\
+             Hamburg,Paris,8,\n\
+             Lyon,Paris,3,50\n"
+        );
+        assert_eq!(write_supply(&problem), "name,supply\nHamburg,76\nLyon,82\n");
+        assert_eq!(write_demand(&problem), "name,demand\nBerlin,72\nParis,86\n");
+    }
+
+    #[rstest]
+    fn round_trip() {
+        let mut problem = GenConfig::default().gen(8);
+        problem.capacities = vec![(0, 1, 7), (3, 3, 2)];
+        // Edge lists don't list forbidden routes, nor their capacities
+        problem.forbidden.retain(|x| *x != (0, 1) && *x != (3, 3));
+        let edges = read_edges::<i32>(
+            &write_edges(&problem),
+            &write_supply(&problem),
+            &write_demand(&problem),
+        )
+        .unwrap();
+        let matrix = read_matrix::<i32>(&write_matrix(&problem)).unwrap();
+        let weights = problem.weights();
+        assert_eq!(edges.weights(), weights);
+        assert_eq!(matrix.weights(), weights);
+        assert_eq!(edges.capacity_grid(), problem.capacity_grid());
+        assert_eq!(
+            (edges.supply, edges.demand),
+            (problem.supply.clone(), problem.demand.clone())
+        );
+        assert_eq!(
+            (matrix.supply, matrix.demand),
+            (problem.supply, problem.demand)
+        );
+    }
+
+    #[rstest]
+    #[case(",A,Supply\nX,1,2,3\nDemand,2\n", 2, "Expected 3 cells, found 4")]
+    #[case(",A,Supply\nX,one,2\nDemand,2\n", 2, "Invalid number \"one\"")]
+    #[case(",A,Supply\nX,1,2\nDemand,2,2,2\n", 3, "Expected 1 demands")]
+    #[case(",A,Supply\n\nX,1,2\n\nDemand,x\n", 5, "Invalid number \"x\"")]
+    #[case(",A\nDemand,1\n", 1, "Expected a Supply column after the destinations")]
+    #[case(",A,Supply\nX,1,2\n", 2, "Expected a Demand row after the sources")]
+    #[case(",A,Supply\n", 1, "Expected a Demand row after the sources")]
+    fn matrix_errors(#[case] text: &str, #[case] line: u64, #[case] message: &str) {
+        assert_eq!(
+            read_matrix::<i32>(text).unwrap_err(),
+            SolverError::Parse {
+                line,
+                message: message.to_string()
+            }
+        );
+    }
+
+    #[rstest]
+    #[case("from,to,cost\nX,A,1\nZ,A,1\n", 3, "Unknown source \"Z\"")]
+    #[case("from,to,cost\nX,B,1\n", 2, "Unknown destination \"B\"")]
+    #[case(
+        "from,to,cost\nX,A,1\nX,A,2\n",
+        3,
+        "Duplicate route from \"X\" to \"A\""
+    )]
+    #[case("from,to,cost\nX,A\n", 2, "Expected from, to, cost and capacity")]
+    #[case("from,to,cost\nX,A,1,big\n", 2, "Invalid number \"big\"")]
+    fn edge_errors(#[case] edges: &str, #[case] line: u64, #[case] message: &str) {
+        assert_eq!(
+            read_edges::<i32>(edges, "name,supply\nX,1\nY,1\n", "name,demand\nA,2\n").unwrap_err(),
+            SolverError::Parse {
+                line,
+                message: message.to_string()
+            }
+        );
+    }
+
+    #[rstest]
+    fn duplicate_name() {
+        assert_eq!(
+            read_edges::<i32>(
+                "from,to,cost\n",
+                "name,supply\nX,1\nX,1\n",
+                "name,demand\nA,2\n"
+            )
+            .unwrap_err(),
+            SolverError::Parse {
+                line: 3,
+                message: "Duplicate name \"X\"".to_string()
+            }
+        );
+    }
+
+    #[rstest]
+    fn empty() {
+        assert_eq!(read_matrix::<i32>("\n\n").unwrap_err(), SolverError::Empty);
+        assert_eq!(
+            TransportationSolver::new(read_matrix::<i32>(",Supply\nDemand\n").unwrap()).err(),
+            Some(SolverError::Empty)
+        );
+    }
+
+    #[rstest]
+    #[case(vec!["X".to_string()], vec![], "source names", 2, 1)]
+    #[case(vec![], vec!["A".to_string(); 3], "destination names", 2, 3)]
+    fn names(
+        #[case] sources: Vec<String>,
+        #[case] destinations: Vec<String>,
+        #[case] what: &str,
+        #[case] expected: usize,
+        #[case] found: usize,
+    ) {
+        let problem = Problem {
+            sources,
+            destinations,
+            ..GenConfig::default().gen(2)
+        };
+        assert_eq!(
+            problem.validate(),
+            Err(SolverError::DimensionMismatch {
+                what: what.to_string(),
+                expected,
+                found
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+mod sparse {
+    use super::*;
+    use crate::solver::warm::Change;
+    use rand::prelude::*;
+    use rstest::*;
+
+    fn lane(source: usize, destination: usize, cost: i32) -> Lane {
+        Lane {
+            source,
+            destination,
+            cost,
+            capacity: None,
+        }
+    }
+
+    fn solve_sparse(problem: SparseProblem) -> (TransportationSolver, Result<(), SolverError>) {
+        let mut solver =
+            TransportationSolver::sparse(problem, Balancing::default()).expect("Invalid problem");
+        let result = solver.solve();
+        (solver, result)
+    }
+
+    /// Number of routes, which the solver stores
+    fn stored(solver: &TransportationSolver) -> usize {
+        serde_json::to_value(solver).expect("Failed to serialize a solver")["routes"]["cells"]
+            .as_array()
+            .expect("Routes are not serialized")
+            .len()
+    }
+
+    /// Assignment, where every source has its own destination
+    /// and a few other random lanes
+    fn generate(rng: &mut StdRng, n: usize, degree: usize) -> SparseProblem {
+        let mut lanes = vec![];
+        for i in 0..n {
+            let mut destinations = vec![i];
+            while destinations.len() <= degree {
+                let j = rng.gen_range(0..n);
+                if !destinations.contains(&j) {
+                    destinations.push(j);
+                }
+            }
+            lanes.extend(
+                destinations
+                    .into_iter()
+                    .map(|j| lane(i, j, rng.gen_range(0..=100))),
+            );
+        }
+        lanes.shuffle(rng);
+        SparseProblem {
+            supply: vec![1; n],
+            demand: vec![1; n],
+            lanes,
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    fn round_trip() {
+        let mut problem = GenConfig::default().gen(6);
+        problem.forbidden = vec![(0, 1), (3, 3)];
+        problem.capacities = vec![(2, 2, 4), (5, 0, 0)];
+        let sparse = problem.sparse();
+        assert_eq!(sparse.lanes.len(), 34);
+        let dense = sparse.dense();
+        assert_eq!(dense.weights(), problem.weights());
+        assert_eq!(dense.capacity_grid(), problem.capacity_grid());
+    }
+
+    #[rstest]
+    fn duplicate_route() {
+        let problem = SparseProblem {
+            supply: vec![5, 5],
+            demand: vec![10],
+            lanes: vec![lane(0, 0, 1), lane(1, 0, 2), lane(0, 0, 3)],
+            ..Default::default()
+        };
+        assert_eq!(
+            problem.validate(),
+            Err(SolverError::DuplicateRoute {
+                source: 0,
+                destination: 0
+            })
+        );
+    }
+
+    #[rstest]
+    fn route_out_of_bounds() {
+        let problem = SparseProblem {
+            supply: vec![5, 5],
+            demand: vec![10],
+            lanes: vec![lane(0, 0, 1), lane(1, 1, 2)],
+            ..Default::default()
+        };
+        let result = TransportationSolver::sparse(problem, Balancing::default());
+        assert_eq!(
+            result.err(),
+            Some(SolverError::RouteOutOfBounds {
+                source: 1,
+                destination: 1
+            })
+        );
+    }
+
+    #[rstest]
+    fn exercise() {
+        let expected = solve_exercise(false);
+        let problem = problem_of(&expected);
+        let lanes = problem
+            .costs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| row.iter().enumerate().map(move |(j, x)| lane(i, j, *x)))
+            .filter(|x| x.cost != M)
+            .collect();
+        let (solver, result) = solve_sparse(SparseProblem {
+            lanes,
+            ..problem.sparse()
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(stats(&solver).objective, stats(&expected).objective);
+        verify(&solver);
+    }
+
+    #[rstest]
+    fn unreachable_destination() {
+        let (solver, result) = solve_sparse(SparseProblem {
+            supply: vec![5, 5],
+            demand: vec![5, 5],
+            lanes: vec![lane(0, 1, 1), lane(1, 1, 2)],
+            ..Default::default()
+        });
+        assert_eq!(result, Err(SolverError::Infeasible));
+        assert!(!stats(&solver).feasible);
+    }
+
+    #[rstest]
+    fn unmet_demand() {
+        let (solver, result) = solve_sparse(SparseProblem {
+            supply: vec![5, 5],
+            demand: vec![4, 8],
+            lanes: vec![lane(0, 0, 1), lane(0, 1, 3), lane(1, 1, 2)],
+            ..Default::default()
+        });
+        assert_eq!(result, Ok(()));
+        let solution = solver.solution().expect("Solved problem has a solution");
+        assert_eq!(solution.shipment(1, 0), 0);
+        assert_eq!(stats(&solver).objective, 4 + 3 + 10);
+    }
+
+    #[rstest]
+    #[case(6)]
+    #[case(10)]
+    #[case(30)]
+    fn generated(#[case] n: usize) {
+        let mut rng = StdRng::seed_from_u64(n as u64);
+        for _ in 0..10 {
+            let problem = GenConfig {
+                forbid_m_vals: false,
+                ..Default::default()
+            }
+            .gen(n);
+            let (expected, _) = try_solve::<Vogel>(problem.clone());
+            let mut lanes = problem
+                .sparse()
+                .lanes
+                .into_iter()
+                .filter(|x| x.cost != M)
+                .collect::<Vec<_>>();
+            lanes.shuffle(&mut rng);
+            let (solver, result) = solve_sparse(SparseProblem {
+                lanes,
+                ..problem.sparse()
+            });
+            let objective = stats(&expected).objective;
+            if objective < M {
+                assert_eq!(result, Ok(()));
+                assert_eq!(stats(&solver).objective, objective);
+            } else {
+                assert_eq!(result, Err(SolverError::Infeasible));
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(100, 3)]
+    #[case(400, 4)]
+    fn stores_few_routes(#[case] n: usize, #[case] degree: usize) {
+        let mut rng = StdRng::seed_from_u64(n as u64);
+        let problem = generate(&mut rng, n, degree);
+        let lanes = problem.lanes.len();
+        let (solver, result) = solve_sparse(problem);
+        assert_eq!(result, Ok(()));
+        assert!(stored(&solver) <= lanes + 2 * n + 1);
+        verify(&solver);
+    }
+
+    #[rstest]
+    fn resolves() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut problem = generate(&mut rng, 50, 3);
+        let (mut solver, result) = solve_sparse(problem.clone());
+        assert_eq!(result, Ok(()));
+        for _ in 0..10 {
+            let idx = rng.gen_range(0..problem.lanes.len());
+            let value = rng.gen_range(0..=100);
+            problem.lanes[idx].cost = value;
+            let change = Change::Cost {
+                source: problem.lanes[idx].source,
+                destination: problem.lanes[idx].destination,
+                value,
+            };
+            solver.resolve(&[change]).expect("Failed to resolve");
+            let (expected, _) = solve_sparse(problem.clone());
+            assert_eq!(stats(&solver).objective, stats(&expected).objective);
+        }
+    }
+}
+
+#[cfg(test)]
+mod large {
+    use super::*;
+    use crate::solver::pricing::*;
+    use rand::prelude::*;
+    use rstest::*;
+    use std::thread;
+
+    /// Much smaller than the default stack of spawned threads
+    const STACK_SIZE: usize = 256 * 1024;
+
+    /// Solves the problem on a thread with a small stack
+    fn solve_on_small_stack<I, P>(problem: SparseProblem) -> TransportationSolver
+    where
+        I: InitialSolution,
+        P: Pricing,
+    {
+        thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn(move || {
+                let mut solver = TransportationSolver::sparse(problem, Balancing::default())
+                    .expect("Invalid problem");
+                solver.solve_using::<I, P>().expect("Failed to solve");
+                solver
+            })
+            .expect("Failed to spawn a thread")
+            .join()
+            .expect("Solver panicked")
+    }
+
+    /// Every source can serve its own destination, the next one and a few random
+    /// others. The north-west corner solution follows the staircase, so the
+    /// initial basis is a path through all rows and columns
+    fn staircase(rng: &mut StdRng, n: usize, degree: usize) -> SparseProblem {
+        let supply = (0..n).map(|_| rng.gen_range(1..=20)).collect::<Vec<_>>();
+        let mut lanes = vec![];
+        for i in 0..n {
+            let mut destinations = vec![i, (i + 1) % n];
+            while destinations.len() < degree + 2 {
+                let j = rng.gen_range(0..n);
+                if !destinations.contains(&j) {
+                    destinations.push(j);
+                }
+            }
+            lanes.extend(destinations.into_iter().map(|j| Lane {
+                source: i,
+                destination: j,
+                cost: rng.gen_range(0..=100),
+                capacity: None,
+            }));
+        }
+        SparseProblem {
+            demand: supply.clone(),
+            supply,
+            lanes,
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    fn staircase_on_small_stack() {
+        let mut rng = StdRng::seed_from_u64(2000);
+        let solver = solve_on_small_stack::<NorthWest, Partial>(staircase(&mut rng, 2000, 2));
+        verify(&solver);
+    }
+
+    #[rstest]
+    fn assignment_on_small_stack() {
+        let n = 2000;
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut problem = staircase(&mut rng, n, 3);
+        problem.supply = vec![1; n];
+        problem.demand = vec![1; n];
+        let solver = solve_on_small_stack::<LeastCost, Partial>(problem);
+        verify(&solver);
+    }
+}
+
+#[cfg(test)]
+mod generation {
+    use super::*;
+    use rand::prelude::*;
+    use rstest::*;
+
+    fn seeded(seed: u64, family: Family) -> GenConfig {
+        GenConfig {
+            seed: Some(seed),
+            family,
+            ..Default::default()
+        }
+    }
+
+    fn assert_same(a: &Problem, b: &Problem) {
+        assert_eq!(a.costs, b.costs);
+        assert_eq!(a.supply, b.supply);
+        assert_eq!(a.demand, b.demand);
+    }
+
+    #[rstest]
+    #[case(Family::Uniform)]
+    #[case(Family::Geometric)]
+    #[case(Family::Clustered { clusters: 3 })]
+    #[case(Family::Degenerate)]
+    fn repeatable(#[case] family: Family) {
+        let config = seeded(7, family);
+        assert_same(&config.gen_rect(8, 5), &config.gen_rect(8, 5));
+        let other = GenConfig {
+            seed: Some(8),
+            ..config.clone()
+        };
+        assert_ne!(config.gen_rect(8, 5).costs, other.gen_rect(8, 5).costs);
+
+        // Seeded generator gives the same series
+        let series = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..3)
+                .map(|_| config.gen_with(&mut rng, 8, 5))
+                .collect::<Vec<_>>()
+        };
+        for (a, b) in series(7).iter().zip(series(7).iter()) {
+            assert_same(a, b);
+        }
+    }
+
+    #[rstest]
+    #[case(Family::Uniform, 3, 12)]
+    #[case(Family::Geometric, 12, 3)]
+    #[case(Family::Clustered { clusters: 4 }, 20, 30)]
+    #[case(Family::Degenerate, 15, 6)]
+    #[case(Family::Degenerate, 6, 15)]
+    fn rectangular(#[case] family: Family, #[case] m: usize, #[case] n: usize) {
+        let problem = seeded(1, family).gen_rect(m, n);
+        assert_eq!(problem.costs.len(), m);
+        assert!(problem.costs.iter().all(|row| row.len() == n));
+        assert_eq!((problem.supply.len(), problem.demand.len()), (m, n));
+        assert_eq!(problem.total_supply(), problem.total_demand());
+        solve_problem(problem, true);
+    }
+
+    #[rstest]
+    #[case(0.5)]
+    #[case(1.5)]
+    fn unbalanced(#[case] demand_ratio: f32) {
+        for family in [Family::Uniform, Family::Geometric] {
+            let problem = GenConfig {
+                demand_ratio,
+                ..seeded(2, family)
+            }
+            .gen_rect(10, 14);
+            let expected = (problem.total_supply() as f32 * demand_ratio).round() as i32;
+            assert_eq!(problem.total_demand(), expected);
+            solve_problem(problem, true);
+        }
+    }
+
+    #[rstest]
+    fn forbidden_m_vals() {
+        let config = seeded(6, Family::Uniform);
+        for n in [6, 10, 30] {
+            let problem = config.gen(n);
+            let priced = GenConfig {
+                forbid_m_vals: false,
+                ..config.clone()
+            }
+            .gen(n);
+            let blocked = (0..n)
+                .flat_map(|i| (0..n).map(move |j| (i, j)))
+                .filter(|(i, j)| priced.costs[*i][*j] == M)
+                .collect::<Vec<_>>();
+            assert_eq!(problem.forbidden, blocked);
+            assert!(problem.costs.iter().flatten().all(|x| *x != M));
+            assert_eq!(problem.sparse().lanes.len(), n * n - blocked.len());
+
+            let (expected, _) = try_solve::<NorthWest>(priced);
+            let (solver, result) = try_solve::<NorthWest>(problem);
+            match stats(&expected).objective < M {
+                true => assert_eq!(stats(&solver).objective, stats(&expected).objective),
+                false => assert_eq!(result, Err(SolverError::Infeasible)),
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(Family::Geometric, 1, 10)]
+    #[case(Family::Clustered { clusters: 5 }, 1, 20)]
+    fn cost_range(#[case] family: Family, #[case] min: i32, #[case] max: i32) {
+        let problem = seeded(3, family).gen(40);
+        let costs = problem.costs.iter().flatten();
+        assert!(costs.clone().all(|x| (min..=max).contains(x)));
+        assert_eq!(costs.clone().min(), Some(&min));
+    }
+
+    #[rstest]
+    fn clusters_are_cheaper_inside() {
+        let problem = seeded(4, Family::Clustered { clusters: 2 }).gen(30);
+        let max_value = GenConfig::default().max_value;
+        // Every source has a destination in its own group
+        for row in problem.costs.iter() {
+            assert!(row.iter().any(|x| *x <= max_value));
+        }
+        assert!(problem.costs.iter().flatten().any(|x| *x > max_value));
+    }
+
+    #[rstest]
+    #[case(10, 10)]
+    #[case(20, 8)]
+    fn degenerate(#[case] m: usize, #[case] n: usize) {
+        let problem = seeded(5, Family::Degenerate).gen_rect(m, n);
+        let weights = problem.weights();
+        let costs = |i: usize, j: usize| weights[i][j];
+        let empty = NorthWest::allocate(&problem.sparse(), &costs)
+            .into_iter()
+            .filter(|(_, _, val)| val.is_zero())
+            .count();
+        assert!(empty >= n - 1);
+        solve_problem(problem, true);
+    }
+}
+
 #[cfg(test)]
 mod artifact {
     use super::*;
+    use rand::prelude::*;
     use rstest::*;
 
     #[rstest]
     fn create_artifact() {
+        let mut rng = StdRng::seed_from_u64(0);
         let stats = (5..100)
             .step_by(2)
             .map(|x| {
                 (0..10)
-                    .map(|_| solve_generated(x, false).stats.unwrap())
+                    .map(|_| {
+                        let problem = GenConfig::default().gen_with(&mut rng, x, x);
+                        solve_problem(problem, false).stats.unwrap()
+                    })
                     .collect::<Vec<_>>()
             })
             .collect::<Vec<_>>();
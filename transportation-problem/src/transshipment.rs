@@ -0,0 +1,178 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SolverError;
+use crate::number::Number;
+use crate::problem::{Balancing, Problem};
+use crate::solver::TransportationSolver;
+use crate::sparse::validate_amounts;
+
+/// Node of a transshipment network
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Node {
+    Source(usize),
+    /// Intermediate node, which passes goods on, like a cross-dock
+    Hub(usize),
+    Sink(usize),
+}
+
+/// Route between two nodes, goods can't enter sources or leave sinks
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Arc<T = i32> {
+    pub from: Node,
+    pub to: Node,
+    pub cost: T,
+}
+
+/// Network, where goods may pass through hubs on their way from sources to sinks
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Transshipment<T = i32> {
+    pub supply: Vec<T>,
+    /// Number of hubs
+    pub hubs: usize,
+    pub demand: Vec<T>,
+    pub arcs: Vec<Arc<T>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransshipmentSolution<T = i32> {
+    /// Goods shipped along every arc, in the order of arcs.
+    /// Out of parallel arcs only the cheapest one is used
+    pub flows: Vec<T>,
+    pub objective: T,
+    /// Goods passing through every hub
+    pub throughput: Vec<T>,
+    /// Demand of each sink, which can't be met
+    pub unmet_demand: Vec<T>,
+    /// Supply of each source, which isn't needed
+    pub leftover_supply: Vec<T>,
+}
+
+impl<T: Number> Transshipment<T> {
+    /// Cell of the transportation problem for the arc. Rows are sources and hubs,
+    /// columns are hubs and sinks
+    fn cell(&self, arc: &Arc<T>) -> Option<(usize, usize)> {
+        let (m, h) = (self.supply.len(), self.hubs);
+        let row = match arc.from {
+            Node::Source(i) if i < m => i,
+            Node::Hub(i) if i < h => m + i,
+            _ => return None,
+        };
+        let col = match arc.to {
+            Node::Hub(j) if j < h && arc.from != Node::Hub(j) => j,
+            Node::Sink(j) if j < self.demand.len() => h + j,
+            _ => return None,
+        };
+        Some((row, col))
+    }
+
+    /// Checks that the data is consistent, so that the network can be solved
+    pub fn validate(&self) -> Result<(), SolverError<T>> {
+        if self.supply.is_empty() || self.demand.is_empty() {
+            return Err(SolverError::Empty);
+        }
+        if let Some(arc) = self.arcs.iter().position(|arc| self.cell(arc).is_none()) {
+            return Err(SolverError::InvalidArc { arc });
+        }
+        validate_amounts(&self.supply, &self.demand)
+    }
+
+    /// Equivalent balanced transportation problem, along with the arc of every cell.
+    /// Every hub is both a source and a destination of the total supply, and goods
+    /// it doesn't pass on are shipped to itself for free. Missing arcs are forbidden.
+    /// The difference of supply and demand is taken by an extra row or column, which
+    /// is connected only to sinks or sources, so that hubs can't create or lose goods
+    fn reduce(&self) -> (Problem<T>, Vec<Vec<Option<usize>>>) {
+        let (sources, hubs, sinks) = (self.supply.len(), self.hubs, self.demand.len());
+        let (m, n) = (sources + hubs, hubs + sinks);
+        let total_supply = self.supply.iter().copied().sum::<T>();
+        let total_demand = self.demand.iter().copied().sum::<T>();
+
+        let mut arcs = vec![vec![None; n]; m];
+        for (idx, arc) in self.arcs.iter().enumerate() {
+            let (i, j) = self.cell(arc).expect("Arc should be valid");
+            if arcs[i][j].is_none_or(|x: usize| arc.cost.compare(&self.arcs[x].cost).is_lt()) {
+                arcs[i][j] = Some(idx);
+            }
+        }
+
+        let mut problem = Problem {
+            costs: vec![vec![T::zero(); n]; m],
+            supply: self.supply.clone(),
+            demand: vec![total_supply; hubs],
+            ..Default::default()
+        };
+        problem.supply.extend(vec![total_supply; hubs]);
+        problem.demand.extend(self.demand.iter().copied());
+        for (i, row) in arcs.iter().enumerate() {
+            for (j, arc) in row.iter().enumerate() {
+                match arc {
+                    Some(idx) => problem.costs[i][j] = self.arcs[*idx].cost,
+                    None if i >= sources && i - sources == j => {}
+                    None => problem.forbidden.push((i, j)),
+                }
+            }
+        }
+
+        match total_supply.compare(&total_demand) {
+            Ordering::Equal => {}
+            // Unmet demand comes from an extra source
+            Ordering::Less => {
+                problem.costs.push(vec![T::zero(); n]);
+                problem.supply.push(total_demand - total_supply);
+                problem.forbidden.extend((0..hubs).map(|j| (m, j)));
+            }
+            // Leftover supply goes to an extra destination
+            Ordering::Greater => {
+                for row in problem.costs.iter_mut() {
+                    row.push(T::zero());
+                }
+                problem.demand.push(total_supply - total_demand);
+                problem.forbidden.extend((sources..m).map(|i| (i, n)));
+            }
+        }
+        (problem, arcs)
+    }
+
+    /// Solves the equivalent transportation problem and maps its plan back to arcs
+    pub fn solve(&self) -> Result<TransshipmentSolution<T>, SolverError<T>> {
+        self.validate()?;
+        let (sources, hubs, sinks) = (self.supply.len(), self.hubs, self.demand.len());
+        let (problem, arcs) = self.reduce();
+        let (m, n) = (problem.supply.len(), problem.demand.len());
+
+        let mut solver = TransportationSolver::with_balancing(problem, Balancing::Strict)?;
+        solver.solve()?;
+        let solution = solver.solution().expect("Solver should be solved");
+
+        let mut flows = vec![T::zero(); self.arcs.len()];
+        let mut throughput = vec![T::zero(); hubs];
+        let mut unmet_demand = vec![T::zero(); sinks];
+        let mut leftover_supply = vec![T::zero(); sources];
+        for route in solution.routes() {
+            let (i, j, amount) = (route.source, route.destination, route.amount);
+            if let Some(idx) = arcs.get(i).and_then(|row| row.get(j)).copied().flatten() {
+                flows[idx] = amount;
+            }
+            // Goods a hub ships to itself don't pass through it
+            if j < hubs && i < sources + hubs && i != sources + j {
+                throughput[j] += amount;
+            }
+            if i == sources + hubs && m > sources + hubs {
+                unmet_demand[j - hubs] = amount;
+            }
+            if j == hubs + sinks && n > hubs + sinks {
+                leftover_supply[i] = amount;
+            }
+        }
+
+        Ok(TransshipmentSolution {
+            flows,
+            objective: solution.objective,
+            throughput,
+            unmet_demand,
+            leftover_supply,
+        })
+    }
+}
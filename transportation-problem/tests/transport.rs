@@ -0,0 +1,192 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use transportation_problem::solution::Solution;
+
+/// Writes the file into a directory of the test
+fn file(test: &str, name: &str, text: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(test);
+    fs::create_dir_all(&dir).expect("Failed to create a directory");
+    let path = dir.join(name);
+    fs::write(&path, text).expect("Failed to write a file");
+    path
+}
+
+fn transport(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_transport"))
+        .args(args)
+        .output()
+        .expect("Failed to run the binary")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).expect("Output is not UTF-8")
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).expect("Output is not UTF-8")
+}
+
+const MATRIX: &str = "\
+,A,B,C,Supply
+S1,3,1,7,25
+S2,2,6,4,25
+Demand,20,10,20
+";
+
+const JSON: &str = r#"{
+    "costs": [[3, 1, 7], [2, 6, 4]],
+    "supply": [25, 25],
+    "demand": [20, 10, 20]
+}"#;
+
+#[test]
+fn csv_matrix() {
+    let path = file("csv_matrix", "problem.csv", MATRIX);
+    let output = transport(&[path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    let text = stdout(&output);
+    assert!(text.contains("Objective: 145"), "{text}");
+    assert!(text.contains("S1    A   15      45\n"), "{text}");
+    assert!(text.starts_with("From  To  Amount  Cost\n"), "{text}");
+}
+
+#[test]
+fn json_by_extension() {
+    let path = file("json_by_extension", "problem.json", JSON);
+    let output = transport(&[path.to_str().unwrap(), "--output", "json"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    let solution: Solution<i64> =
+        serde_json::from_str(&stdout(&output)).expect("Output is not a solution");
+    assert_eq!(solution.objective, 145);
+}
+
+#[test]
+fn input_overrides_extension() {
+    let path = file("input_overrides_extension", "problem.txt", JSON);
+    let output = transport(&[path.to_str().unwrap(), "--input", "json"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    // Without the option it is read as a CSV matrix
+    let output = transport(&[path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn edge_list() {
+    let test = "edge_list";
+    let edges = file(
+        test,
+        "edges.csv",
+        "from,to,cost,capacity\nS1,A,3\nS1,B,1,5\nS2,A,2\nS2,B,6\n",
+    );
+    let supply = file(test, "supply.csv", "name,supply\nS1,10\nS2,10\n");
+    let demand = file(test, "demand.csv", "name,demand\nA,12\nB,8\n");
+    let output = transport(&[
+        edges.to_str().unwrap(),
+        "--supply",
+        supply.to_str().unwrap(),
+        "--demand",
+        demand.to_str().unwrap(),
+    ]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    let text = stdout(&output);
+    // Cheap route from S1 to B is capped at 5
+    assert!(text.contains("S1    B   5       5\n"), "{text}");
+    assert!(text.contains("Objective: 52"), "{text}");
+}
+
+#[test]
+fn edge_list_rejects_input_format() {
+    let test = "edge_list_rejects_input_format";
+    let edges = file(test, "edges.csv", "from,to,cost\nS1,A,3\n");
+    let supply = file(test, "supply.csv", "name,supply\nS1,10\n");
+    let demand = file(test, "demand.csv", "name,demand\nA,10\n");
+    let output = transport(&[
+        edges.to_str().unwrap(),
+        "--supply",
+        supply.to_str().unwrap(),
+        "--demand",
+        demand.to_str().unwrap(),
+        "--input",
+        "json",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("cannot be used with"));
+}
+
+#[test]
+fn infeasible() {
+    let path = file(
+        "infeasible",
+        "problem.json",
+        r#"{
+            "costs": [[1, 2], [3, 4]],
+            "supply": [5, 5],
+            "demand": [5, 5],
+            "forbidden": [[0, 0], [1, 0]]
+        }"#,
+    );
+    let output = transport(&[path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stdout(&output).is_empty());
+}
+
+#[test]
+fn iteration_limit() {
+    let path = file("iteration_limit", "problem.json", JSON);
+    let output = transport(&[path.to_str().unwrap(), "--max-iterations", "0"]);
+    assert_eq!(output.status.code(), Some(4), "{}", stderr(&output));
+}
+
+#[test]
+fn invalid_number() {
+    let path = file(
+        "invalid_number",
+        "problem.csv",
+        ",A,Supply\nS1,x,2\nDemand,2\n",
+    );
+    let output = transport(&[path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("error: "));
+}
+
+#[test]
+fn missing_file() {
+    let output = transport(&["no-such-problem.json"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("Can't read no-such-problem.json"));
+}
+
+#[test]
+fn strict() {
+    let path = file(
+        "strict",
+        "problem.csv",
+        &MATRIX.replace("S2,2,6,4,25", "S2,2,6,4,20"),
+    );
+    let output = transport(&[path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Unmet demand:"));
+
+    let output = transport(&[path.to_str().unwrap(), "--strict"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn rational() {
+    let path = file(
+        "rational",
+        "problem.csv",
+        ",A,B,Supply\nS1,1/2,3/4,3/2\nDemand,1,1/2\n",
+    );
+    let output = transport(&[path.to_str().unwrap(), "--numbers", "rational"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Objective: 7/8"));
+}
+
+#[test]
+fn wrong_argument() {
+    let output = transport(&["problem.json", "--pricing", "cheapest"]);
+    assert_eq!(output.status.code(), Some(2));
+}